@@ -9,8 +9,58 @@ pub const VAULT_AUTHORITY: &str = "divi-vault-authority";
 #[constant]
 pub const PARTICIPANT_VAULT: &str = "participant_vault";
 
+// Seed of the participant vault authorities that escrowed contributions before they moved to the
+// vault authority, only signed for to migrate their funds
 #[constant]
 pub const PARTICIPANT_VAULT_AUTHORITY: &str = "participant_vault_authority";
 
+#[constant]
+pub const ISSUER_PROFILE: &str = "issuer_profile";
+
+#[constant]
+pub const PAYMENT_METADATA: &str = "payment_metadata";
+
+// Payment ids from this one are allocated by the issuer profile in `create_vault`, the ids below
+// are picked by the issuer in `initialize_vault`
+#[constant]
+pub const FIRST_PROFILE_PAYMENT_ID: u32 = 1 << 31;
+
+#[constant]
+pub const NATIVE_DECIMALS: u8 = 9;
+
+#[constant]
+pub const MAX_INVOICE_SHARES: usize = 10;
+
+#[constant]
+pub const MAX_PAYOUT_RECIPIENTS: usize = 5;
+
+#[constant]
+pub const MAX_MILESTONES: usize = 8;
+
+// Seconds the arbiter has to resolve a dispute when the issuer doesn't pick another window,
+// contributors can claim their refund once it's over
+#[constant]
+pub const DEFAULT_DISPUTE_WINDOW: i64 = 14 * 24 * 60 * 60;
+
+#[constant]
+pub const EVENT_VERSION: u8 = 1;
+
+#[constant]
+pub const BASIS_POINTS: u64 = 10_000;
+
+#[constant]
+pub const MAX_TITLE_LENGTH: usize = 64;
+
+#[constant]
+pub const MAX_DESCRIPTION_LENGTH: usize = 256;
+
+#[constant]
+pub const MAX_CURRENCY_LENGTH: usize = 10;
+
+#[constant]
+pub const MAX_INVOICE_REFERENCE_LENGTH: usize = 64;
+
+#[constant]
+pub const MAX_URI_LENGTH: usize = 200;
+
 pub const ANCHOR_DISCRIMINATOR: usize = 8;
-pub const LAMPORTS_PER_SOL: u64 = 1_000_000_000;
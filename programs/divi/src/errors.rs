@@ -46,4 +46,78 @@ pub enum DiviError {
     InvalidParticipantVault,
     #[msg("All participant are not refunded")]
     NotAllParticipantsRefunded,
+    #[msg("The mint doesn't match the vault mint")]
+    MintMismatch,
+    #[msg("Arithmetic overflow")]
+    ArithmeticOverflow,
+    #[msg("The expiration must be in the future")]
+    InvalidExpiration,
+    #[msg("Vault is expired")]
+    VaultIsExpired,
+    #[msg("Vault is cancelled")]
+    VaultIsCancelled,
+    #[msg("Vault is neither cancelled nor expired")]
+    RefundNotClaimable,
+    #[msg("The withdrawal amount must be between 1 and the participant contribution")]
+    InvalidWithdrawalAmount,
+    #[msg("The vault balance is lower than the collected amount")]
+    InsufficientVaultBalance,
+    #[msg("Invoice shares must be unique, non zero and add up to the total amount")]
+    InvalidInvoiceShares,
+    #[msg("Participant is not a debtor of this invoice")]
+    ParticipantNotInInvoice,
+    #[msg("The amount doesn't match the participant share")]
+    AmountDoesNotMatchShare,
+    #[msg("The recipient doesn't match the vault recipient")]
+    InvalidRecipient,
+    #[msg("The recipient can't be changed once the vault received a contribution")]
+    RecipientIsLocked,
+    #[msg("Payout recipients must be unique and their basis points must add up to 10 000")]
+    InvalidPayoutRecipients,
+    #[msg("The vault pays several recipients, use distribute instead")]
+    PayoutRecipientsConfigured,
+    #[msg("The vault has no payout recipients")]
+    NoPayoutRecipients,
+    #[msg("Milestones can't exceed the total amount and can't be changed once the vault received a contribution")]
+    InvalidMilestones,
+    #[msg("Milestone doesn't exist")]
+    MilestoneNotFound,
+    #[msg("Milestone is already released")]
+    MilestoneAlreadyReleased,
+    #[msg("Milestone is already approved by this participant")]
+    MilestoneAlreadyApproved,
+    #[msg("Milestone doesn't have enough approvals")]
+    MilestoneNotApproved,
+    #[msg(
+        "The release quorum is invalid or can't be changed once the vault received a contribution"
+    )]
+    InvalidReleaseQuorum,
+    #[msg("The vault has no release quorum")]
+    NoReleaseQuorum,
+    #[msg("Release is already approved by this participant")]
+    ReleaseAlreadyApproved,
+    #[msg("Release doesn't have enough approvals")]
+    ReleaseNotApproved,
+    #[msg("The arbiter is invalid or can't be changed once the vault received a contribution")]
+    InvalidArbiter,
+    #[msg("The vault has no arbiter")]
+    NoArbiter,
+    #[msg("Vault is disputed")]
+    VaultIsDisputed,
+    #[msg("Vault is not disputed")]
+    VaultIsNotDisputed,
+    #[msg("Vault is not closed")]
+    VaultIsNotClosed,
+    #[msg("Remaining accounts must be writable (participant, participant vault) pairs")]
+    InvalidRefundBatch,
+    #[msg("Metadata can't be changed once the vault received a contribution")]
+    MetadataIsLocked,
+    #[msg("Amount must be greater than zero")]
+    InvalidAmount,
+    #[msg("Payment vault is invalid")]
+    InvalidPaymentVault,
+    #[msg("The arbiter already ruled on a dispute of this vault")]
+    DisputeAlreadyResolved,
+    #[msg("The dispute window must be positive")]
+    InvalidDisputeWindow,
 }
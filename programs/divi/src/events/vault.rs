@@ -1,38 +1,243 @@
 use anchor_lang::prelude::*;
 
+use crate::states::{DisputeResolution, PaymentMetadataArgs, VaultStatus};
+
+// Every event starts with the `EVENT_VERSION` it was emitted with, and carries the vault
+// collected and remaining amounts after the instruction, along with the `Clock` timestamp.
+// Events of an already closed vault only carry the amount moved
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct Payout {
+    pub recipient: Pubkey,
+    // Amount sent to the recipient, in base units of the vault asset
+    pub amount: u64,
+}
+
 #[event]
 pub struct VaultCreated {
+    pub version: u8,
     pub issuer: Pubkey,
     pub payment_id: u32,
     pub bump: u8,
+    // The vault total amount
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+    pub metadata: Option<PaymentMetadataArgs>,
+}
+
+#[event]
+pub struct ParticipantJoined {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    pub participant: Pubkey,
+    // The first contribution of the participant
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub participant_count: u32,
+    pub timestamp: i64,
 }
 
 #[event]
 pub struct ParticipantPaid {
+    pub version: u8,
     pub issuer: Pubkey,
     pub payer: Pubkey,
     pub payment_id: u32,
     pub bump: u8,
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
 }
 
 #[event]
 pub struct VaultCompleted {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    // The contribution completing the vault
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultReleased {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    pub mint: Option<Pubkey>,
+    // The whole amount paid out, donations to the vault authority included
+    pub amount: u64,
+    pub payouts: Vec<Payout>,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultClosed {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    // The status the vault was closed in, never released
+    pub status: VaultStatus,
+    // The lamports swept back to the issuer
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct VaultAuthorityRecovered {
+    pub version: u8,
     pub issuer: Pubkey,
     pub payment_id: u32,
     pub bump: u8,
+    // The token mint, `None` for lamports
+    pub mint: Option<Pubkey>,
+    // The amount sent back to the issuer
+    pub amount: u64,
+    pub timestamp: i64,
 }
 
 #[event]
 pub struct VaultCancelled {
+    pub version: u8,
     pub issuer: Pubkey,
     pub payment_id: u32,
     pub bump: u8,
+    // The escrowed amount left to refund to the participants
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
 }
 
 #[event]
 pub struct ParticipantRefunded {
+    pub version: u8,
     pub issuer: Pubkey,
     pub payment_id: u32,
     pub bump: u8,
     pub participant: Pubkey,
+    // The amount sent back, lower than the contribution once milestones are released
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ParticipantWithdrew {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    pub participant: Pubkey,
+    pub amount: u64,
+    // The contribution left on the participant ledger
+    pub participant_amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneReleased {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    pub index: u8,
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub released_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MilestoneApproved {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    pub participant: Pubkey,
+    pub index: u8,
+    // The amount released by the milestone
+    pub amount: u64,
+    // Approvals of the milestone, this one included
+    pub approvals: u8,
+    pub approvals_required: u8,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct ReleaseApproved {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    pub participant: Pubkey,
+    // The contribution of the approving participant
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeOpened {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    pub participant: Pubkey,
+    // The contribution of the disputing participant
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct DisputeResolved {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    pub arbiter: Pubkey,
+    pub resolution: DisputeResolution,
+    // The escrowed amount the dispute was about
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+}
+
+#[event]
+pub struct MetadataUpdated {
+    pub version: u8,
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub bump: u8,
+    // The vault total amount
+    pub amount: u64,
+    pub collected_amount: u64,
+    pub remaining_amount: u64,
+    pub timestamp: i64,
+    pub metadata: PaymentMetadataArgs,
 }
@@ -0,0 +1,100 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, PARTICIPANT_VAULT, VAULT},
+    errors::DiviError,
+    events::MilestoneApproved,
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+};
+
+/**
+* Let a contributor approve the release of a milestone, once
+*/
+pub fn handler(ctx: Context<ApproveMilestone>, payment_id: u32, index: u8) -> Result<()> {
+    ctx.accounts
+        .vault
+        .require_status(&[VaultStatus::Open, VaultStatus::Funded])?;
+
+    let participant_vault = &mut ctx.accounts.participant_vault;
+    let milestone = ctx
+        .accounts
+        .vault
+        .milestones
+        .get_mut(index as usize)
+        .ok_or(DiviError::MilestoneNotFound)?;
+
+    require!(!milestone.is_released, DiviError::MilestoneAlreadyReleased);
+    require!(
+        participant_vault.approved_milestones & (1 << index) == 0,
+        DiviError::MilestoneAlreadyApproved
+    );
+
+    participant_vault.approved_milestones |= 1 << index;
+    milestone.approvals = milestone
+        .approvals
+        .checked_add(1)
+        .ok_or(DiviError::ArithmeticOverflow)?;
+
+    let amount = milestone.amount;
+    let approvals = milestone.approvals;
+    let approvals_required = milestone.approvals_required;
+    let vault = &ctx.accounts.vault;
+
+    emit!(MilestoneApproved {
+        version: EVENT_VERSION,
+        issuer: vault.issuer,
+        payment_id,
+        bump: vault.bump,
+        participant: participant_vault.participant,
+        index,
+        amount,
+        approvals,
+        approvals_required,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Participant {} approved milestone {} of payment {}",
+        ctx.accounts.participant.key(),
+        index,
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct ApproveMilestone<'info> {
+    /// CHECK: The contributor approving the milestone
+    pub participant: Signer<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The participant vault, proof of the contribution
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.amount > 0 @ DiviError::InvalidParticipant,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+}
@@ -0,0 +1,93 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, PARTICIPANT_VAULT, VAULT},
+    errors::DiviError,
+    events::ReleaseApproved,
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+};
+
+/**
+* Let a contributor approve the release of the funds of a funded vault, once
+*/
+pub fn handler(ctx: Context<ApproveRelease>, payment_id: u32) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+    let participant_vault = &mut ctx.accounts.participant_vault;
+
+    vault.require_status(&[VaultStatus::Funded])?;
+
+    // Check if the release deadline is not over, contributors can reclaim their funds after it
+    require!(
+        !vault.is_release_expired(Clock::get()?.unix_timestamp),
+        DiviError::VaultIsExpired
+    );
+    require!(
+        !participant_vault.has_approved_release,
+        DiviError::ReleaseAlreadyApproved
+    );
+
+    participant_vault.has_approved_release = true;
+    vault.release_approvals = vault
+        .release_approvals
+        .checked_add(1)
+        .ok_or(DiviError::ArithmeticOverflow)?;
+    vault.release_approved_amount = vault
+        .release_approved_amount
+        .checked_add(participant_vault.amount)
+        .ok_or(DiviError::ArithmeticOverflow)?;
+
+    emit!(ReleaseApproved {
+        version: EVENT_VERSION,
+        issuer: vault.issuer,
+        payment_id,
+        bump: vault.bump,
+        participant: participant_vault.participant,
+        amount: participant_vault.amount,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Participant {} approved the release of payment {}",
+        participant_vault.participant,
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct ApproveRelease<'info> {
+    /// CHECK: The contributor approving the release
+    pub participant: Signer<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.release_quorum.is_some() @ DiviError::NoReleaseQuorum,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The participant vault, proof of the contribution
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.amount > 0 @ DiviError::InvalidParticipant,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+}
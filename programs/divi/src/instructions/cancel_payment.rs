@@ -1,31 +1,28 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::{VAULT, VAULT_AUTHORITY},
+    constants::{EVENT_VERSION, VAULT, VAULT_AUTHORITY},
     errors::DiviError,
     events::VaultCancelled,
-    states::PaymentVault,
-    utils::is_valid_participant_vault,
+    states::{PaymentVault, VaultStatus},
 };
 
 pub fn handler(ctx: Context<CancelPayment>, payment_id: u32) -> Result<()> {
     let vault = &mut ctx.accounts.vault;
 
-    // Check if all payment participant account are closed
-    if !ctx.remaining_accounts.is_empty() {
-        for account_info in ctx.remaining_accounts.iter() {
-            if is_valid_participant_vault(account_info, payment_id, ctx.program_id)? {
-                return err!(DiviError::NotAllParticipantsRefunded);
-            }
-        }
-    }
-
-    vault.is_cancelled = true;
+    // Participants still in the vault claim their refund once it's cancelled, they're counted
+    // on-chain so closing the vault waits for every refund
+    vault.transition(VaultStatus::Cancelled)?;
 
     emit!(VaultCancelled {
-        issuer: ctx.accounts.issuer.key().clone(),
-        payment_id: payment_id.clone(),
-        bump: vault.bump.clone(),
+        version: EVENT_VERSION,
+        issuer: ctx.accounts.issuer.key(),
+        payment_id,
+        bump: vault.bump,
+        amount: vault.escrowed_amount()?,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
     });
 
     msg!("Payment {} cancelled by issuer", payment_id);
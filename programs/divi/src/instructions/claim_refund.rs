@@ -0,0 +1,112 @@
+use crate::{
+    constants::{EVENT_VERSION, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::ParticipantRefunded,
+    states::{ParticipantVault, PaymentVault},
+    utils::transfer_from_vault,
+};
+use anchor_lang::prelude::*;
+
+/**
+* Let a participant pull his own contribution back once the vault is cancelled, expired without being funded, or
+* its release isn't approved in time
+*/
+pub fn handler(ctx: Context<ClaimRefund>, payment_id: u32) -> Result<()> {
+    let participant_key = ctx.accounts.participant.key();
+
+    // Check if the vault was cancelled by the issuer, its deadline is over or its release wasn't
+    // approved in time
+    ctx.accounts
+        .vault
+        .open_refunds(Clock::get()?.unix_timestamp)?;
+
+    // Transfer the participant contribution back from the vault authority
+    let amount = ctx
+        .accounts
+        .vault
+        .release_contribution(participant_key, ctx.accounts.participant_vault.amount)?;
+    ctx.accounts
+        .vault
+        .remove_participant(ctx.accounts.participant_vault.approved_milestones)?;
+
+    if amount > 0 {
+        transfer_from_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            &ctx.accounts.participant.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            amount,
+        )?;
+
+        msg!(
+            "Refunded {} lamports to participant {}",
+            amount,
+            participant_key
+        );
+    }
+
+    emit!(ParticipantRefunded {
+        version: EVENT_VERSION,
+        issuer: ctx.accounts.vault.issuer,
+        payment_id,
+        bump: ctx.accounts.vault.bump,
+        participant: participant_key,
+        amount,
+        collected_amount: ctx.accounts.vault.collected_amount,
+        remaining_amount: ctx.accounts.vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct ClaimRefund<'info> {
+    /// CHECK: The participant claiming his refund
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The main vault authority (funds)
+    #[account(
+        mut,
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The participant vault, closed and its rent sent back to the participant
+    #[account(
+        mut,
+        close = participant,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,143 @@
+use crate::{
+    constants::{EVENT_VERSION, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::ParticipantRefunded,
+    states::{ParticipantVault, PaymentVault},
+    utils::transfer_tokens,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/**
+* Let a participant pull his own tokens back once the vault is cancelled, expired without being funded, or
+* its release isn't approved in time
+*/
+pub fn handler(ctx: Context<ClaimRefundToken>, payment_id: u32) -> Result<()> {
+    let participant_key = ctx.accounts.participant.key();
+    let issuer_key = ctx.accounts.vault.issuer;
+
+    // Check if the vault was cancelled by the issuer, its deadline is over or its release wasn't
+    // approved in time
+    ctx.accounts
+        .vault
+        .open_refunds(Clock::get()?.unix_timestamp)?;
+
+    // Prepare seeds for the vault authority
+    let authority_seeds = &[
+        VAULT_AUTHORITY.as_bytes(),
+        issuer_key.as_ref(),
+        &payment_id.to_le_bytes(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // Transfer the participant contribution back from the vault token account
+    let amount = ctx
+        .accounts
+        .vault
+        .release_contribution(participant_key, ctx.accounts.participant_vault.amount)?;
+    ctx.accounts
+        .vault
+        .remove_participant(ctx.accounts.participant_vault.approved_milestones)?;
+
+    if amount > 0 {
+        transfer_tokens(
+            &ctx.accounts.vault_token_account,
+            &mut ctx.accounts.participant_token_account,
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            amount,
+            signer_seeds,
+        )?;
+
+        msg!(
+            "Refunded {} tokens to participant {}",
+            amount,
+            participant_key
+        );
+    }
+
+    emit!(ParticipantRefunded {
+        version: EVENT_VERSION,
+        issuer: issuer_key,
+        payment_id,
+        bump: ctx.accounts.vault.bump,
+        participant: participant_key,
+        amount,
+        collected_amount: ctx.accounts.vault.collected_amount,
+        remaining_amount: ctx.accounts.vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct ClaimRefundToken<'info> {
+    /// CHECK: The participant claiming his refund
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.mint == Some(mint.key()) @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The main vault authority, owner of the vault token account
+    #[account(
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The participant vault, closed and its rent sent back to the participant
+    #[account(
+        mut,
+        close = participant,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: The vault token account (funds)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The participant token account receiving funds
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = participant,
+        token::token_program = token_program,
+    )]
+    pub participant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
@@ -0,0 +1,65 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{PARTICIPANT_VAULT, VAULT},
+    errors::DiviError,
+    states::{ParticipantVault, PaymentVault},
+};
+
+/**
+* Give the rent of a participant vault back to the participant once its payment vault is closed,
+* the vault was either released or left without participants so the ledger holds nothing anymore.
+* The address may already hold a new vault reusing the payment id, the ledger then carries the
+* nonce of the closed one
+*/
+pub fn handler(ctx: Context<CloseParticipantVault>, payment_id: u32) -> Result<()> {
+    let vault_info = ctx.accounts.vault.to_account_info();
+
+    if !vault_info.data_is_empty() {
+        let vault = PaymentVault::try_deserialize(&mut &vault_info.try_borrow_data()?[..])?;
+
+        require!(
+            vault.nonce != ctx.accounts.participant_vault.vault_nonce,
+            DiviError::VaultIsNotClosed
+        );
+    }
+
+    msg!(
+        "Participant {} closed his participant vault of payment {}",
+        ctx.accounts.participant.key(),
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct CloseParticipantVault<'info> {
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    #[account(
+        seeds = [
+            VAULT.as_bytes(),
+            participant_vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    /// CHECK: Closed payment vault, or a new vault at its address, checked in the handler
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: The participant vault, its rent goes back to the participant
+    #[account(
+        mut,
+        close = participant,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+}
@@ -1,21 +1,71 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::VAULT, errors::DiviError, events::VaultCompleted, states::PaymentVault,
-    utils::is_valid_participant_vault,
+    constants::{EVENT_VERSION, PAYMENT_METADATA, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::VaultClosed,
+    states::{PaymentVault, VaultStatus},
+    utils::{close_program_account, transfer_from_vault},
 };
 
+/**
+* Close a vault that doesn't hold any contribution, the vault authority rent and the lamports sent
+* directly to it are swept to the issuer since nothing can sign for it once the vault is closed
+*/
 pub fn handler(ctx: Context<ClosePaymentVault>, payment_id: u32) -> Result<()> {
-    let vault = &mut ctx.accounts.vault;
+    let vault = &ctx.accounts.vault;
 
-    vault.is_finalized = true;
+    // Check if the vault is not funded or disputed, its funds are closed by the release instead
+    vault.require_status(&[
+        VaultStatus::Open,
+        VaultStatus::Cancelled,
+        VaultStatus::Expired,
+    ])?;
+
+    // Check if every participant was refunded or withdrew
+    require!(
+        vault.participant_count == 0,
+        DiviError::NotAllParticipantsRefunded
+    );
+
+    let vault_authority = ctx.accounts.vault_authority.to_account_info();
+    let sweep_amount = vault_authority.lamports();
+
+    if sweep_amount > 0 {
+        transfer_from_vault(
+            vault,
+            &vault_authority,
+            ctx.bumps.vault_authority,
+            &ctx.accounts.issuer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            sweep_amount,
+        )?;
+
+        msg!(
+            "Swept {} lamports of rent and donations from vault authority to issuer",
+            sweep_amount
+        );
+    }
+
+    // Close the payment metadata along with the vault when the issuer set one
+    let metadata = ctx.accounts.metadata.to_account_info();
+
+    if !metadata.data_is_empty() {
+        close_program_account(&metadata, &ctx.accounts.issuer.to_account_info())?;
+    }
 
     msg!("Payment vault {} closed successfully", payment_id);
 
-    emit!(VaultCompleted {
-        issuer: ctx.accounts.issuer.key().clone(),
-        payment_id: payment_id.clone(),
-        bump: vault.bump.clone(),
+    emit!(VaultClosed {
+        version: EVENT_VERSION,
+        issuer: ctx.accounts.issuer.key(),
+        payment_id,
+        bump: vault.bump,
+        status: vault.status,
+        amount: sweep_amount,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
     });
 
     Ok(())
@@ -37,9 +87,28 @@ pub struct ClosePaymentVault<'info> {
         ],
         bump = vault.bump,
         constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
-        constraint = !vault.is_finalized @ DiviError::PaymentAlreadyFinalized,
     )]
     pub vault: Account<'info, PaymentVault>,
 
+    #[account(
+        mut,
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    /// CHECK: PDA for vault authority
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata describing the payment, closed along with the vault when the issuer set one
+    #[account(
+        mut,
+        seeds = [PAYMENT_METADATA.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
@@ -1,16 +1,20 @@
 use crate::{
-    constants::{VAULT, VAULT_AUTHORITY},
+    constants::{EVENT_VERSION, PAYMENT_METADATA, VAULT, VAULT_AUTHORITY},
     errors::DiviError,
-    states::PaymentVault,
+    events::{Payout, VaultReleased},
+    states::{PaymentVault, VaultStatus},
+    utils::{close_program_account, vault_authority_rent},
 };
 use anchor_lang::prelude::*;
 
 /**
-* Close the vault and send SOL amount and rent to the issuer
+* Close the vault, send the collected SOL amount to the recipient and the rents to the issuer
 */
 pub fn handler(ctx: Context<CloseVault>, payment_id: u32) -> Result<()> {
     let issuer_key = ctx.accounts.issuer.key();
 
+    ctx.accounts.vault.transition(VaultStatus::Released)?;
+
     // Get the PDA signer seeds for vault_authority
     let authority_seeds = &[
         VAULT_AUTHORITY.as_bytes(),
@@ -20,11 +24,33 @@ pub fn handler(ctx: Context<CloseVault>, payment_id: u32) -> Result<()> {
     ];
     let signer_seeds = &[&authority_seeds[..]];
 
-    // Get vault authority balance (minus rent for safety)
+    // Check if the vault authority holds every contribution still escrowed by the vault
     let vault_authority = &ctx.accounts.vault_authority;
-    let rent = Rent::get()?;
-    let min_rent = rent.minimum_balance(0);
-    let transfer_amount = vault_authority.lamports().saturating_sub(min_rent);
+    let escrowed_amount = ctx.accounts.vault.escrowed_amount()?;
+    let balance = vault_authority.lamports();
+
+    require!(
+        balance >= escrowed_amount,
+        DiviError::InsufficientVaultBalance
+    );
+
+    // Give the vault authority rent back to the issuer
+    let rent = vault_authority_rent(balance, escrowed_amount)?;
+    let transfer_amount = balance - rent;
+
+    if rent > 0 {
+        anchor_lang::system_program::transfer(
+            CpiContext::new_with_signer(
+                ctx.accounts.system_program.to_account_info(),
+                anchor_lang::system_program::Transfer {
+                    from: vault_authority.to_account_info(),
+                    to: ctx.accounts.issuer.to_account_info(),
+                },
+                signer_seeds,
+            ),
+            rent,
+        )?;
+    }
 
     // Transfer SOL from vault_authority to issuer
     if transfer_amount > 0 {
@@ -33,19 +59,47 @@ pub fn handler(ctx: Context<CloseVault>, payment_id: u32) -> Result<()> {
                 ctx.accounts.system_program.to_account_info(),
                 anchor_lang::system_program::Transfer {
                     from: vault_authority.to_account_info(),
-                    to: ctx.accounts.issuer.to_account_info(),
+                    to: ctx.accounts.recipient.to_account_info(),
                 },
                 signer_seeds,
             ),
             transfer_amount,
         )?;
 
+        // Lamports sent directly to the vault authority are not contributions, they're swept
+        // to the recipient so the authority is left empty
         msg!(
-            "Transferred {} lamports from vault authority to issuer",
-            transfer_amount
+            "Transferred {} lamports escrowed and {} lamports donated from vault authority to recipient",
+            escrowed_amount,
+            transfer_amount - escrowed_amount
         );
     }
 
+    let vault = &ctx.accounts.vault;
+
+    emit!(VaultReleased {
+        version: EVENT_VERSION,
+        issuer: issuer_key,
+        payment_id,
+        bump: vault.bump,
+        mint: None,
+        amount: transfer_amount,
+        payouts: vec![Payout {
+            recipient: ctx.accounts.recipient.key(),
+            amount: transfer_amount,
+        }],
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    // Close the payment metadata along with the vault when the issuer set one
+    let metadata = ctx.accounts.metadata.to_account_info();
+
+    if !metadata.data_is_empty() {
+        close_program_account(&metadata, &ctx.accounts.issuer.to_account_info())?;
+    }
+
     msg!("Vault {} closed successfully", payment_id);
     Ok(())
 }
@@ -55,10 +109,17 @@ pub fn handler(ctx: Context<CloseVault>, payment_id: u32) -> Result<()> {
 payment_id: u32
 )]
 pub struct CloseVault<'info> {
-    /// Payment issuer who receives the funds
+    /// Payment issuer who receives the rent
     #[account(mut)]
     pub issuer: Signer<'info>,
 
+    /// CHECK: Recipient who receives the funds, checked against the vault recipient
+    #[account(
+      mut,
+      address = vault.recipient @ DiviError::InvalidRecipient,
+  )]
+    pub recipient: UncheckedAccount<'info>,
+
     /// Payment vault account with metadata
     #[account(
       mut,
@@ -70,7 +131,9 @@ pub struct CloseVault<'info> {
       ],
       bump = vault.bump,
       constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
-      constraint = vault.is_finalized == true @ DiviError::VaultIsNotFinalized,
+      constraint = vault.is_release_approved() @ DiviError::ReleaseNotApproved,
+      constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+      constraint = vault.payout_recipients.is_empty() @ DiviError::PayoutRecipientsConfigured,
   )]
     pub vault: Account<'info, PaymentVault>,
 
@@ -87,5 +150,13 @@ pub struct CloseVault<'info> {
     /// CHECK: This is a PDA that holds the funds
     pub vault_authority: UncheckedAccount<'info>,
 
+    /// CHECK: Metadata describing the payment, closed along with the vault when the issuer set one
+    #[account(
+      mut,
+      seeds = [PAYMENT_METADATA.as_bytes(), vault.key().as_ref()],
+      bump,
+  )]
+    pub metadata: UncheckedAccount<'info>,
+
     pub system_program: Program<'info, System>,
 }
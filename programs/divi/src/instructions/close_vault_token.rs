@@ -0,0 +1,177 @@
+use crate::{
+    constants::{EVENT_VERSION, PAYMENT_METADATA, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::{Payout, VaultReleased},
+    states::{PaymentVault, VaultStatus},
+    utils::{close_program_account, close_token_account, transfer_tokens},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+/**
+* Close the vault, send the tokens to the recipient and the rents back to the issuer
+*/
+pub fn handler(ctx: Context<CloseVaultToken>, payment_id: u32) -> Result<()> {
+    let issuer_key = ctx.accounts.issuer.key();
+
+    ctx.accounts.vault.transition(VaultStatus::Released)?;
+
+    // Get the PDA signer seeds for vault_authority
+    let authority_seeds = &[
+        VAULT_AUTHORITY.as_bytes(),
+        issuer_key.as_ref(),
+        &payment_id.to_le_bytes(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // Check if the vault token account holds every contribution still escrowed by the vault
+    let escrowed_amount = ctx.accounts.vault.escrowed_amount()?;
+    let transfer_amount = ctx.accounts.vault_token_account.amount;
+
+    require!(
+        transfer_amount >= escrowed_amount,
+        DiviError::InsufficientVaultBalance
+    );
+
+    // Transfer tokens from the vault token account to the recipient, tokens sent directly to the
+    // vault token account included since it's closed afterwards
+
+    if transfer_amount > 0 {
+        transfer_tokens(
+            &ctx.accounts.vault_token_account,
+            &mut ctx.accounts.recipient_token_account,
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            transfer_amount,
+            signer_seeds,
+        )?;
+
+        msg!(
+            "Transferred {} tokens escrowed and {} tokens donated from vault authority to recipient",
+            escrowed_amount,
+            transfer_amount - escrowed_amount
+        );
+    }
+
+    // Close the vault token account and send the rent to the issuer
+    close_token_account(
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.issuer.to_account_info(),
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        signer_seeds,
+    )?;
+
+    let vault = &ctx.accounts.vault;
+
+    emit!(VaultReleased {
+        version: EVENT_VERSION,
+        issuer: issuer_key,
+        payment_id,
+        bump: vault.bump,
+        mint: vault.mint,
+        amount: transfer_amount,
+        payouts: vec![Payout {
+            recipient: ctx.accounts.recipient.key(),
+            amount: transfer_amount,
+        }],
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    // Close the payment metadata along with the vault when the issuer set one
+    let metadata = ctx.accounts.metadata.to_account_info();
+
+    if !metadata.data_is_empty() {
+        close_program_account(&metadata, &ctx.accounts.issuer.to_account_info())?;
+    }
+
+    msg!("Vault {} closed successfully", payment_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(
+payment_id: u32
+)]
+pub struct CloseVaultToken<'info> {
+    /// Payment issuer who receives the rents
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    /// CHECK: Recipient who receives the funds, checked against the vault recipient
+    #[account(address = vault.recipient @ DiviError::InvalidRecipient)]
+    pub recipient: UncheckedAccount<'info>,
+
+    /// Payment vault account with metadata
+    #[account(
+      mut,
+      close = issuer,
+      seeds = [
+          VAULT.as_bytes(),
+          issuer.key().as_ref(),
+          &payment_id.to_le_bytes(),
+      ],
+      bump = vault.bump,
+      constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+      constraint = vault.is_release_approved() @ DiviError::ReleaseNotApproved,
+      constraint = vault.mint == Some(mint.key()) @ DiviError::MintMismatch,
+  )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// Vault authority - a PDA that owns the vault token account
+    #[account(
+      seeds = [
+          VAULT_AUTHORITY.as_bytes(),
+          issuer.key().as_ref(),
+          &payment_id.to_le_bytes(),
+      ],
+      bump,
+  )]
+    /// CHECK: This is a PDA that owns the funds
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata describing the payment, closed along with the vault when the issuer set one
+    #[account(
+      mut,
+      seeds = [PAYMENT_METADATA.as_bytes(), vault.key().as_ref()],
+      bump,
+  )]
+    pub metadata: UncheckedAccount<'info>,
+
+    /// Token mint, receives the transfer fees withheld by the vault token account before it closes
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault token account that holds the funds
+    #[account(
+      mut,
+      associated_token::mint = mint,
+      associated_token::authority = vault_authority,
+      associated_token::token_program = token_program,
+  )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Recipient token account receiving the funds
+    #[account(
+      init_if_needed,
+      payer = issuer,
+      associated_token::mint = mint,
+      associated_token::authority = recipient,
+      associated_token::token_program = token_program,
+  )]
+    pub recipient_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
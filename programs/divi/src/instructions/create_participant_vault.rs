@@ -1,31 +1,41 @@
 use crate::{
-    constants::{ANCHOR_DISCRIMINATOR, PARTICIPANT_VAULT, PARTICIPANT_VAULT_AUTHORITY, VAULT},
+    constants::{ANCHOR_DISCRIMINATOR, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
     states::{ParticipantVault, PaymentVault},
+    utils::record_contribution,
 };
 use anchor_lang::prelude::*;
 
 pub fn handler(ctx: Context<CreateParticipantVault>, payment_id: u32, amount: u64) -> Result<()> {
-    let participant_vault = &mut ctx.accounts.participant_vault;
-
-    // Intialize the participant vault
-    participant_vault.participant = ctx.accounts.participant.key();
-    participant_vault.amount = amount;
-    participant_vault.payment_id = payment_id;
-    participant_vault.issuer = ctx.accounts.vault.issuer;
-    participant_vault.bump = ctx.bumps.participant_vault;
-
-    // Transfer SOL from participant wallet to participant vault authority
+    // Transfer SOL from participant wallet to the vault authority, where it stays refundable
+    // until the vault is finalized
     anchor_lang::system_program::transfer(
         CpiContext::new(
             ctx.accounts.system_program.to_account_info(),
             anchor_lang::system_program::Transfer {
                 from: ctx.accounts.participant.to_account_info(),
-                to: ctx.accounts.participant_vault_authority.to_account_info(),
+                to: ctx.accounts.vault_authority.to_account_info(),
             },
         ),
         amount,
     )?;
 
+    // Intialize the participant vault and count the contribution toward the vault
+    ctx.accounts.participant_vault.bump = ctx.bumps.participant_vault;
+
+    record_contribution(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.participant_vault,
+        ctx.accounts.participant.key(),
+        amount,
+    )?;
+
+    msg!(
+        "Participant {} joined payment {}",
+        ctx.accounts.participant.key(),
+        payment_id
+    );
+
     Ok(())
 }
 
@@ -41,6 +51,7 @@ pub struct CreateParticipantVault<'info> {
       mut,
       seeds = [VAULT.as_bytes(), vault.issuer.as_ref(), &payment_id.to_le_bytes()],
       bump = vault.bump,
+      constraint = vault.mint.is_none() @ DiviError::MintMismatch,
   )]
     pub vault: Account<'info, PaymentVault>,
 
@@ -51,24 +62,24 @@ pub struct CreateParticipantVault<'info> {
       space = ANCHOR_DISCRIMINATOR + ParticipantVault::INIT_SPACE,
       seeds = [
           PARTICIPANT_VAULT.as_bytes(),
+          vault.key().as_ref(),
           participant.key().as_ref(),
-          &payment_id.to_le_bytes(),
       ],
       bump,
     )]
     pub participant_vault: Account<'info, ParticipantVault>,
 
-    /// CHECK: Participant vault authority (funds)
+    /// CHECK: Vault authority - a PDA that holds the funds
     #[account(
         mut,
       seeds = [
-          PARTICIPANT_VAULT_AUTHORITY.as_bytes(),
-          participant.key().as_ref(),
+          VAULT_AUTHORITY.as_bytes(),
+          vault.issuer.as_ref(),
           &payment_id.to_le_bytes(),
       ],
       bump,
     )]
-    pub participant_vault_authority: UncheckedAccount<'info>,
+    pub vault_authority: UncheckedAccount<'info>,
 
     pub system_program: Program<'info, System>,
 }
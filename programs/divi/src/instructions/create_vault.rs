@@ -0,0 +1,152 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
+
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, EVENT_VERSION, ISSUER_PROFILE, NATIVE_DECIMALS, PAYMENT_METADATA,
+        VAULT, VAULT_AUTHORITY,
+    },
+    errors::DiviError,
+    events::VaultCreated,
+    states::{
+        InvoiceShareArgs, IssuerProfile, PaymentMetadata, PaymentMetadataArgs, PaymentVault,
+        SplitMode,
+    },
+    utils::{fund_vault_authority, initialize_terms},
+};
+
+/**
+* Create a vault with the next payment id of the issuer profile, so clients don't have to pick a
+* unique one, along with the metadata describing the payment
+*/
+pub fn handler(
+    ctx: Context<CreateVault>,
+    total_amount: u64,
+    expires_at: Option<i64>,
+    recipient: Option<Pubkey>,
+    split_mode: SplitMode,
+    shares: Vec<InvoiceShareArgs>,
+    metadata: PaymentMetadataArgs,
+) -> Result<()> {
+    let issuer_profile = &mut ctx.accounts.issuer_profile;
+    let payment_id = issuer_profile.payment_id();
+
+    issuer_profile.issuer = ctx.accounts.issuer.key();
+    issuer_profile.bump = ctx.bumps.issuer_profile;
+    issuer_profile.next_payment_id = payment_id
+        .checked_add(1)
+        .ok_or(DiviError::ArithmeticOverflow)?;
+
+    let vault = &mut ctx.accounts.vault;
+
+    vault.issuer = ctx.accounts.issuer.key();
+    vault.payment_id = payment_id;
+    vault.nonce = issuer_profile.next_vault_nonce()?;
+    vault.bump = ctx.bumps.vault;
+    vault.authority = ctx.accounts.vault_authority.key();
+    vault.mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
+    vault.decimals = ctx
+        .accounts
+        .mint
+        .as_ref()
+        .map_or(NATIVE_DECIMALS, |mint| mint.decimals);
+
+    initialize_terms(
+        vault,
+        total_amount,
+        expires_at,
+        recipient,
+        split_mode,
+        shares,
+    )?;
+
+    if vault.mint.is_none() {
+        fund_vault_authority(
+            &ctx.accounts.issuer.to_account_info(),
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+    }
+
+    let payment_metadata = &mut ctx.accounts.metadata;
+
+    payment_metadata.vault = ctx.accounts.vault.key();
+    payment_metadata.bump = ctx.bumps.metadata;
+    payment_metadata.update(metadata.clone())?;
+
+    emit!(VaultCreated {
+        version: EVENT_VERSION,
+        issuer: ctx.accounts.issuer.key(),
+        payment_id,
+        bump: ctx.bumps.vault,
+        amount: total_amount,
+        collected_amount: 0,
+        remaining_amount: total_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+        metadata: Some(metadata),
+    });
+
+    msg!("Payment vault {} created", payment_id);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+pub struct CreateVault<'info> {
+    /// CHECK: Payment issuer
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    /// CHECK: Issuer profile allocating the payment ids and counting the vaults, created with the
+    /// first vault
+    #[account(
+        init_if_needed,
+        payer = issuer,
+        space = ANCHOR_DISCRIMINATOR + IssuerProfile::INIT_SPACE,
+        seeds = [ISSUER_PROFILE.as_bytes(), issuer.key().as_ref()],
+        bump,
+    )]
+    pub issuer_profile: Account<'info, IssuerProfile>,
+
+    /// CHECK: Payment vault account
+    #[account(
+        init,
+        payer = issuer,
+        space = ANCHOR_DISCRIMINATOR + PaymentVault::INIT_SPACE,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &issuer_profile.payment_id().to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: Vault authority - a PDA that will have authority over the vault, funded with its rent
+    /// for a SOL vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            issuer.key().as_ref(),
+            &issuer_profile.payment_id().to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata describing the payment
+    #[account(
+        init,
+        payer = issuer,
+        space = ANCHOR_DISCRIMINATOR + PaymentMetadata::INIT_SPACE,
+        seeds = [PAYMENT_METADATA.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub metadata: Account<'info, PaymentMetadata>,
+
+    /// CHECK: SPL Token or Token-2022 mint, omitted for a native SOL payment
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+}
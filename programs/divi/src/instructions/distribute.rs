@@ -0,0 +1,159 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, PAYMENT_METADATA, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::{Payout, VaultReleased},
+    states::{PaymentVault, VaultStatus},
+    utils::{close_program_account, split_amount, transfer_from_vault, vault_authority_rent},
+};
+
+/**
+* Close the vault, share the collected SOL amount between the payout recipients and send the
+* rents to the issuer. Recipients are passed as remaining accounts, in the order of the vault list
+*/
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+    payment_id: u32,
+) -> Result<()> {
+    ctx.accounts.vault.transition(VaultStatus::Released)?;
+
+    let vault = &ctx.accounts.vault;
+
+    // Check if the remaining accounts match the stored payout recipients
+    require!(
+        ctx.remaining_accounts.len() == vault.payout_recipients.len(),
+        DiviError::InvalidRecipient
+    );
+
+    for (account_info, payout) in ctx.remaining_accounts.iter().zip(&vault.payout_recipients) {
+        require!(
+            account_info.key() == payout.recipient && account_info.is_writable,
+            DiviError::InvalidRecipient
+        );
+    }
+
+    // Check if the vault authority holds every contribution still escrowed by the vault, lamports
+    // sent directly to it are shared as well so the authority is left empty
+    let escrowed_amount = vault.escrowed_amount()?;
+    let balance = ctx.accounts.vault_authority.lamports();
+
+    require!(
+        balance >= escrowed_amount,
+        DiviError::InsufficientVaultBalance
+    );
+
+    // Give the vault authority rent back to the issuer
+    let rent = vault_authority_rent(balance, escrowed_amount)?;
+    let transfer_amount = balance - rent;
+
+    if rent > 0 {
+        transfer_from_vault(
+            vault,
+            &ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            &ctx.accounts.issuer.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            rent,
+        )?;
+    }
+
+    let basis_points: Vec<u64> = vault
+        .payout_recipients
+        .iter()
+        .map(|payout| payout.basis_points as u64)
+        .collect();
+    let amounts = split_amount(transfer_amount, &basis_points)?;
+
+    let mut payouts = Vec::with_capacity(amounts.len());
+
+    for (account_info, amount) in ctx.remaining_accounts.iter().zip(amounts) {
+        payouts.push(Payout {
+            recipient: account_info.key(),
+            amount,
+        });
+
+        if amount > 0 {
+            transfer_from_vault(
+                vault,
+                &ctx.accounts.vault_authority.to_account_info(),
+                ctx.bumps.vault_authority,
+                account_info,
+                &ctx.accounts.system_program.to_account_info(),
+                amount,
+            )?;
+
+            msg!("Transferred {} lamports to {}", amount, account_info.key());
+        }
+    }
+
+    emit!(VaultReleased {
+        version: EVENT_VERSION,
+        issuer: vault.issuer,
+        payment_id,
+        bump: vault.bump,
+        mint: None,
+        amount: transfer_amount,
+        payouts,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    // Close the payment metadata along with the vault when the issuer set one
+    let metadata = ctx.accounts.metadata.to_account_info();
+
+    if !metadata.data_is_empty() {
+        close_program_account(&metadata, &ctx.accounts.issuer.to_account_info())?;
+    }
+
+    msg!("Vault {} distributed successfully", payment_id);
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct Distribute<'info> {
+    /// Payment issuer who receives the rent
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    /// Payment vault account with metadata
+    #[account(
+        mut,
+        close = issuer,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+        constraint = vault.is_release_approved() @ DiviError::ReleaseNotApproved,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+        constraint = !vault.payout_recipients.is_empty() @ DiviError::NoPayoutRecipients,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: Vault authority - a PDA that holds the SOL funds
+    #[account(
+        mut,
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Metadata describing the payment, closed along with the vault when the issuer set one
+    #[account(
+        mut,
+        seeds = [PAYMENT_METADATA.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub metadata: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,86 @@
+use crate::{
+    constants::{PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    states::{ParticipantVault, PaymentVault},
+    utils::record_contribution,
+};
+use anchor_lang::prelude::*;
+
+/**
+* Add lamports to an existing participation, capped by the vault remaining amount
+*/
+pub fn handler(ctx: Context<IncreaseParticipation>, payment_id: u32, amount: u64) -> Result<()> {
+    // Transfer SOL from participant wallet to the vault authority, where it stays refundable
+    // until the vault is finalized
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: ctx.accounts.participant.to_account_info(),
+                to: ctx.accounts.vault_authority.to_account_info(),
+            },
+        ),
+        amount,
+    )?;
+
+    // Count the top-up toward the participant ledger and the vault
+    record_contribution(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.participant_vault,
+        ctx.accounts.participant.key(),
+        amount,
+    )?;
+
+    msg!(
+        "Participant {} increased his participation to payment {}",
+        ctx.accounts.participant.key(),
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32, amount: u64)]
+pub struct IncreaseParticipation<'info> {
+    /// CHECK: Payment participant
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    /// CHECK: Main payment vault (from the issuer)
+    #[account(
+      mut,
+      seeds = [VAULT.as_bytes(), vault.issuer.as_ref(), &payment_id.to_le_bytes()],
+      bump = vault.bump,
+      constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+  )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: Existing participant vault
+    #[account(
+      mut,
+      seeds = [
+          PARTICIPANT_VAULT.as_bytes(),
+          vault.key().as_ref(),
+          participant.key().as_ref(),
+      ],
+      bump = participant_vault.bump,
+      constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+      constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    /// CHECK: Vault authority - a PDA that holds the funds
+    #[account(
+        mut,
+      seeds = [
+          VAULT_AUTHORITY.as_bytes(),
+          vault.issuer.as_ref(),
+          &payment_id.to_le_bytes(),
+      ],
+      bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,110 @@
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+use crate::{
+    constants::{PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    states::{ParticipantVault, PaymentVault},
+    utils::{record_contribution, transfer_tokens},
+};
+
+/**
+* Add tokens to an existing participation, capped by the vault remaining amount
+*/
+pub fn handler(
+    ctx: Context<IncreaseParticipationToken>,
+    payment_id: u32,
+    amount: u64,
+) -> Result<()> {
+    // Transfer tokens from participant wallet to the vault token account, where they stay
+    // refundable until the vault is finalized
+    let received_amount = transfer_tokens(
+        &ctx.accounts.participant_token_account,
+        &mut ctx.accounts.vault_token_account,
+        &ctx.accounts.participant.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+        &[],
+    )?;
+
+    // Count the top-up credited, transfer fees excluded
+    record_contribution(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.participant_vault,
+        ctx.accounts.participant.key(),
+        received_amount,
+    )?;
+
+    msg!(
+        "Participant {} increased his participation to payment {}",
+        ctx.accounts.participant.key(),
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32, amount: u64)]
+pub struct IncreaseParticipationToken<'info> {
+    /// CHECK: Payment participant
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    /// CHECK: Main payment vault (from the issuer)
+    #[account(
+        mut,
+        seeds = [VAULT.as_bytes(), vault.issuer.as_ref(), &payment_id.to_le_bytes()],
+        bump = vault.bump,
+        constraint = vault.mint == Some(mint.key()) @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: Existing participant vault
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    /// CHECK: Vault authority - a PDA that owns the vault token account
+    #[account(
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Participant token account
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = participant,
+        token::token_program = token_program,
+    )]
+    pub participant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Vault token account holding the funds
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
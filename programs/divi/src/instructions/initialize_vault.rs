@@ -1,25 +1,78 @@
 use anchor_lang::prelude::*;
+use anchor_spl::token_interface::Mint;
 
-use crate::{constants::{ANCHOR_DISCRIMINATOR, VAULT, VAULT_AUTHORITY}, events::VaultCreated, states::PaymentVault};
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, EVENT_VERSION, FIRST_PROFILE_PAYMENT_ID, ISSUER_PROFILE,
+        NATIVE_DECIMALS, VAULT, VAULT_AUTHORITY,
+    },
+    errors::DiviError,
+    events::VaultCreated,
+    states::{InvoiceShareArgs, IssuerProfile, PaymentVault, SplitMode},
+    utils::{fund_vault_authority, initialize_terms},
+};
 
 pub fn handler(
     ctx: Context<InitializeVault>,
     payment_id: u32,
     total_amount: u64,
+    expires_at: Option<i64>,
+    recipient: Option<Pubkey>,
+    split_mode: SplitMode,
+    shares: Vec<InvoiceShareArgs>,
 ) -> Result<()> {
+    // Check if the payment id is not in the range allocated by the issuer profile
+    require!(
+        payment_id < FIRST_PROFILE_PAYMENT_ID,
+        DiviError::InvalidPaymentId
+    );
+
+    let issuer_profile = &mut ctx.accounts.issuer_profile;
+
+    issuer_profile.issuer = ctx.accounts.issuer.key();
+    issuer_profile.bump = ctx.bumps.issuer_profile;
+
     let vault = &mut ctx.accounts.vault;
 
     vault.issuer = ctx.accounts.issuer.key();
-    vault.total_amount = total_amount;
-    vault.is_finalized = false;
     vault.payment_id = payment_id;
+    vault.nonce = issuer_profile.next_vault_nonce()?;
     vault.bump = ctx.bumps.vault;
     vault.authority = ctx.accounts.vault_authority.key();
+    vault.mint = ctx.accounts.mint.as_ref().map(|mint| mint.key());
+    vault.decimals = ctx
+        .accounts
+        .mint
+        .as_ref()
+        .map_or(NATIVE_DECIMALS, |mint| mint.decimals);
+
+    initialize_terms(
+        vault,
+        total_amount,
+        expires_at,
+        recipient,
+        split_mode,
+        shares,
+    )?;
+
+    if vault.mint.is_none() {
+        fund_vault_authority(
+            &ctx.accounts.issuer.to_account_info(),
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+        )?;
+    }
 
     emit!(VaultCreated {
-        issuer: ctx.accounts.issuer.key().clone(),
-        payment_id: payment_id.clone(),
-        bump: ctx.bumps.vault.clone(),
+        version: EVENT_VERSION,
+        issuer: ctx.accounts.issuer.key(),
+        payment_id,
+        bump: ctx.bumps.vault,
+        amount: total_amount,
+        collected_amount: 0,
+        remaining_amount: total_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+        metadata: None,
     });
 
     Ok(())
@@ -34,9 +87,19 @@ pub struct InitializeVault<'info> {
     #[account(mut, signer)]
     pub issuer: Signer<'info>,
 
+    /// CHECK: Issuer profile counting the vaults of the issuer, created with the first vault
+    #[account(
+        init_if_needed,
+        payer = issuer,
+        space = ANCHOR_DISCRIMINATOR + IssuerProfile::INIT_SPACE,
+        seeds = [ISSUER_PROFILE.as_bytes(), issuer.key().as_ref()],
+        bump,
+    )]
+    pub issuer_profile: Account<'info, IssuerProfile>,
+
     /// CHECK: Payment vault account
     #[account(
-        init, 
+        init,
         payer = issuer,
         space = ANCHOR_DISCRIMINATOR + PaymentVault::INIT_SPACE,
         seeds = [
@@ -47,9 +110,11 @@ pub struct InitializeVault<'info> {
         bump,
     )]
     pub vault: Account<'info, PaymentVault>,
-    
-    /// CHECK: Vault authority - a PDA that will have authority over the vault
+
+    /// CHECK: Vault authority - a PDA that will have authority over the vault, funded with its rent
+    /// for a SOL vault
     #[account(
+        mut,
         seeds = [
             VAULT_AUTHORITY.as_bytes(),
             issuer.key().as_ref(),
@@ -58,6 +123,9 @@ pub struct InitializeVault<'info> {
         bump,
     )]
     pub vault_authority: UncheckedAccount<'info>,
-    
-    pub system_program: Program<'info, System>
-}
\ No newline at end of file
+
+    /// CHECK: SPL Token or Token-2022 mint, omitted for a native SOL payment
+    pub mint: Option<InterfaceAccount<'info, Mint>>,
+
+    pub system_program: Program<'info, System>,
+}
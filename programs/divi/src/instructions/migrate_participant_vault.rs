@@ -0,0 +1,238 @@
+use anchor_lang::{prelude::*, system_program};
+
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, EVENT_VERSION, PARTICIPANT_VAULT, PARTICIPANT_VAULT_AUTHORITY, VAULT,
+        VAULT_AUTHORITY,
+    },
+    errors::DiviError,
+    events::ParticipantRefunded,
+    states::{LegacyParticipantVault, LegacyPaymentVault, ParticipantVault, PaymentVault},
+    utils::{close_program_account, create_program_account, record_contribution},
+};
+
+/**
+* Move a participant vault derived from the legacy `[participant, payment_id]` seeds to the seeds
+* derived from the vault address. The contribution escrowed by the legacy participant vault
+* authority moves to the vault authority and counts toward the vault like a new contribution.
+* When the vault can't take it (closed, still in the legacy layout, paid in tokens, no longer
+* open or full) the contribution is refunded to the participant instead
+*/
+pub fn handler(ctx: Context<MigrateParticipantVault>, payment_id: u32) -> Result<()> {
+    let participant_key = ctx.accounts.participant.key();
+    let legacy_participant_vault_info = ctx.accounts.legacy_participant_vault.to_account_info();
+    let legacy_participant_vault =
+        LegacyParticipantVault::try_from_bytes(&legacy_participant_vault_info.try_borrow_data()?)?;
+
+    // Check if the legacy participant vault belongs to this vault and participant
+    require!(
+        legacy_participant_vault.issuer == ctx.accounts.issuer.key()
+            && legacy_participant_vault.payment_id == payment_id
+            && legacy_participant_vault.participant == participant_key,
+        DiviError::InvalidParticipantVault
+    );
+
+    // Check if the legacy participant vault authority still holds the contribution
+    let legacy_authority = &ctx.accounts.legacy_participant_vault_authority;
+    let transfer_amount = legacy_authority.lamports();
+
+    require!(
+        transfer_amount >= legacy_participant_vault.amount,
+        DiviError::InsufficientVaultBalance
+    );
+
+    // Load the vault, a legacy vault keeps its contributions on the vault authority without
+    // participant vaults so it can't account for this one
+    let vault_info = ctx.accounts.vault.to_account_info();
+    let vault = if vault_info.owner != &crate::ID || vault_info.data_is_empty() {
+        None
+    } else {
+        let data = vault_info.try_borrow_data()?;
+
+        if LegacyPaymentVault::try_from_bytes(&data).is_ok() {
+            None
+        } else {
+            Some(PaymentVault::try_deserialize(&mut &data[..])?)
+        }
+    };
+
+    let participant_vault_info = ctx.accounts.participant_vault.to_account_info();
+    let mut participant_vault = if participant_vault_info.data_is_empty() {
+        ParticipantVault::default()
+    } else {
+        ParticipantVault::try_deserialize(&mut &participant_vault_info.try_borrow_data()?[..])?
+    };
+
+    // Count the contribution toward a copy of the vault, kept only if the vault accepts it
+    let migrated_vault =
+        vault
+            .clone()
+            .filter(|vault| vault.mint.is_none())
+            .and_then(|mut vault| {
+                record_contribution(
+                    &mut vault,
+                    &mut participant_vault,
+                    participant_key,
+                    legacy_participant_vault.amount,
+                )
+                .ok()
+                .map(|_| vault)
+            });
+
+    let destination = if let Some(migrated_vault) = migrated_vault {
+        // Create the participant vault derived from the vault address on its first contribution
+        if participant_vault_info.data_is_empty() {
+            let vault_key = vault_info.key();
+            let participant_vault_seeds = &[
+                PARTICIPANT_VAULT.as_bytes(),
+                vault_key.as_ref(),
+                participant_key.as_ref(),
+                &[ctx.bumps.participant_vault],
+            ];
+
+            create_program_account(
+                &participant_vault_info,
+                &ctx.accounts.participant.to_account_info(),
+                &ctx.accounts.system_program.to_account_info(),
+                ANCHOR_DISCRIMINATOR + ParticipantVault::INIT_SPACE,
+                &[&participant_vault_seeds[..]],
+            )?;
+        }
+
+        participant_vault.bump = ctx.bumps.participant_vault;
+        participant_vault
+            .try_serialize(&mut &mut participant_vault_info.try_borrow_mut_data()?[..])?;
+        migrated_vault.try_serialize(&mut &mut vault_info.try_borrow_mut_data()?[..])?;
+
+        msg!(
+            "Participant vault of {} migrated for payment {} with {} lamports",
+            participant_key,
+            payment_id,
+            legacy_participant_vault.amount
+        );
+
+        ctx.accounts.vault_authority.to_account_info()
+    } else {
+        emit!(ParticipantRefunded {
+            version: EVENT_VERSION,
+            issuer: legacy_participant_vault.issuer,
+            payment_id,
+            bump: ctx.bumps.vault,
+            participant: participant_key,
+            amount: transfer_amount,
+            collected_amount: vault.as_ref().map_or(0, |vault| vault.collected_amount),
+            remaining_amount: vault.as_ref().map_or(0, |vault| vault.remaining_amount()),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Participant vault of {} refunded for payment {} with {} lamports",
+            participant_key,
+            payment_id,
+            transfer_amount
+        );
+
+        ctx.accounts.participant.to_account_info()
+    };
+
+    // Get the PDA signer seeds for the legacy participant vault authority
+    let authority_seeds = &[
+        PARTICIPANT_VAULT_AUTHORITY.as_bytes(),
+        participant_key.as_ref(),
+        &payment_id.to_le_bytes(),
+        &[ctx.bumps.legacy_participant_vault_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // Empty the legacy participant vault authority, nothing else can sign for it
+    system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            system_program::Transfer {
+                from: legacy_authority.to_account_info(),
+                to: destination,
+            },
+            signer_seeds,
+        ),
+        transfer_amount,
+    )?;
+
+    // Close the legacy participant vault and send the rent to the participant
+    close_program_account(
+        &legacy_participant_vault_info,
+        &ctx.accounts.participant.to_account_info(),
+    )
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct MigrateParticipantVault<'info> {
+    /// CHECK: Payment participant
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    /// CHECK: Payment issuer, checked against the legacy participant vault
+    pub issuer: UncheckedAccount<'info>,
+
+    /// CHECK: Main payment vault (from the issuer), decoded by hand in the handler since it may be
+    /// closed or still in the legacy layout
+    #[account(
+      mut,
+      seeds = [VAULT.as_bytes(), issuer.key().as_ref(), &payment_id.to_le_bytes()],
+      bump,
+  )]
+    pub vault: UncheckedAccount<'info>,
+
+    /// CHECK: Participant vault derived from the legacy seeds, decoded by hand in the handler and
+    /// closed once migrated
+    #[account(
+      mut,
+      owner = crate::ID @ DiviError::InvalidParticipantVault,
+      seeds = [
+          PARTICIPANT_VAULT.as_bytes(),
+          participant.key().as_ref(),
+          &payment_id.to_le_bytes(),
+      ],
+      bump,
+  )]
+    pub legacy_participant_vault: UncheckedAccount<'info>,
+
+    /// CHECK: Legacy participant vault authority (funds)
+    #[account(
+      mut,
+      seeds = [
+          PARTICIPANT_VAULT_AUTHORITY.as_bytes(),
+          participant.key().as_ref(),
+          &payment_id.to_le_bytes(),
+      ],
+      bump,
+  )]
+    pub legacy_participant_vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The main vault authority (funds)
+    #[account(
+      mut,
+      seeds = [
+          VAULT_AUTHORITY.as_bytes(),
+          issuer.key().as_ref(),
+          &payment_id.to_le_bytes(),
+      ],
+      bump,
+  )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: Participant vault derived from the vault address, created by the handler only when
+    /// the contribution moves to the vault
+    #[account(
+      mut,
+      seeds = [
+          PARTICIPANT_VAULT.as_bytes(),
+          vault.key().as_ref(),
+          participant.key().as_ref(),
+      ],
+      bump,
+  )]
+    pub participant_vault: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -1,17 +1,73 @@
 #![allow(ambiguous_glob_reexports)]
 
+pub mod approve_milestone;
+pub mod approve_release;
 pub mod cancel_payment;
+pub mod claim_refund;
+pub mod claim_refund_token;
+pub mod close_participant_vault;
 pub mod close_payment_vault;
 pub mod close_vault;
+pub mod close_vault_token;
 pub mod create_participant_vault;
+pub mod create_vault;
+pub mod distribute;
+pub mod increase_participation;
+pub mod increase_participation_token;
 pub mod initialize_vault;
+pub mod migrate_participant_vault;
+pub mod open_dispute;
+pub mod participate_token;
 pub mod pay;
+pub mod pay_token;
+pub mod recover_vault_authority;
+pub mod recover_vault_authority_token;
 pub mod refund_participant;
+pub mod refund_participant_token;
+pub mod refund_participants_batch;
+pub mod release_milestone;
+pub mod resolve_dispute;
+pub mod set_arbiter;
+pub mod set_metadata;
+pub mod set_milestones;
+pub mod set_payout_recipients;
+pub mod set_recipient;
+pub mod set_release_quorum;
+pub mod withdraw_participation;
+pub mod withdraw_participation_token;
 
+pub use approve_milestone::*;
+pub use approve_release::*;
 pub use cancel_payment::*;
+pub use claim_refund::*;
+pub use claim_refund_token::*;
+pub use close_participant_vault::*;
 pub use close_payment_vault::*;
 pub use close_vault::*;
+pub use close_vault_token::*;
 pub use create_participant_vault::*;
+pub use create_vault::*;
+pub use distribute::*;
+pub use increase_participation::*;
+pub use increase_participation_token::*;
 pub use initialize_vault::*;
+pub use migrate_participant_vault::*;
+pub use open_dispute::*;
+pub use participate_token::*;
 pub use pay::*;
+pub use pay_token::*;
+pub use recover_vault_authority::*;
+pub use recover_vault_authority_token::*;
 pub use refund_participant::*;
+pub use refund_participant_token::*;
+pub use refund_participants_batch::*;
+pub use release_milestone::*;
+pub use resolve_dispute::*;
+pub use set_arbiter::*;
+pub use set_metadata::*;
+pub use set_milestones::*;
+pub use set_payout_recipients::*;
+pub use set_recipient::*;
+pub use set_release_quorum::*;
+pub use withdraw_participation::*;
+pub use withdraw_participation_token::*;
@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, PARTICIPANT_VAULT, VAULT},
+    errors::DiviError,
+    events::DisputeOpened,
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+};
+
+/**
+* Let a contributor freeze the payouts of the vault until the arbiter resolves the dispute, or
+* until the dispute window is over and contributors claim their refund
+*/
+pub fn handler(ctx: Context<OpenDispute>, payment_id: u32) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    vault.transition(VaultStatus::Disputed)?;
+    vault.dispute_deadline = Some(
+        Clock::get()?
+            .unix_timestamp
+            .checked_add(vault.dispute_window)
+            .ok_or(DiviError::ArithmeticOverflow)?,
+    );
+
+    emit!(DisputeOpened {
+        version: EVENT_VERSION,
+        issuer: vault.issuer,
+        payment_id,
+        bump: vault.bump,
+        participant: ctx.accounts.participant.key(),
+        amount: ctx.accounts.participant_vault.amount,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Participant {} disputed payment {}",
+        ctx.accounts.participant.key(),
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct OpenDispute<'info> {
+    /// CHECK: The contributor opening the dispute
+    pub participant: Signer<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.arbiter.is_some() @ DiviError::NoArbiter,
+        constraint = !vault.is_dispute_resolved @ DiviError::DisputeAlreadyResolved,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The participant vault, proof of the contribution
+    #[account(
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.amount > 0 @ DiviError::InvalidParticipant,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+}
@@ -0,0 +1,113 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{ANCHOR_DISCRIMINATOR, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    states::{ParticipantVault, PaymentVault},
+    utils::{record_contribution, transfer_tokens},
+};
+
+pub fn handler(ctx: Context<ParticipateToken>, payment_id: u32, amount: u64) -> Result<()> {
+    // Transfer tokens from participant wallet to the vault token account, where they stay
+    // refundable until the vault is finalized
+    let received_amount = transfer_tokens(
+        &ctx.accounts.participant_token_account,
+        &mut ctx.accounts.vault_token_account,
+        &ctx.accounts.participant.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+        &[],
+    )?;
+
+    // Intialize the participant vault with the amount credited, transfer fees excluded
+    ctx.accounts.participant_vault.bump = ctx.bumps.participant_vault;
+
+    record_contribution(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.participant_vault,
+        ctx.accounts.participant.key(),
+        received_amount,
+    )?;
+
+    msg!(
+        "Participant {} joined payment {}",
+        ctx.accounts.participant.key(),
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32, amount: u64)]
+pub struct ParticipateToken<'info> {
+    /// CHECK: Payment participant
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    /// CHECK: Main payment vault (from the issuer)
+    #[account(
+        mut,
+        seeds = [VAULT.as_bytes(), vault.issuer.as_ref(), &payment_id.to_le_bytes()],
+        bump = vault.bump,
+        constraint = vault.mint == Some(mint.key()) @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: Participant vault
+    #[account(
+        init,
+        payer = participant,
+        space = ANCHOR_DISCRIMINATOR + ParticipantVault::INIT_SPACE,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    /// CHECK: Vault authority - a PDA that owns the vault token account
+    #[account(
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Participant token account
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = participant,
+        token::token_program = token_program,
+    )]
+    pub participant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Vault token account that will hold the funds
+    #[account(
+        init_if_needed,
+        payer = participant,
+        associated_token::mint = mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
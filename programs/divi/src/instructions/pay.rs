@@ -1,30 +1,13 @@
 use anchor_lang::prelude::*;
 
 use crate::{
-    constants::{LAMPORTS_PER_SOL, VAULT, VAULT_AUTHORITY},
+    constants::{ANCHOR_DISCRIMINATOR, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
     errors::DiviError,
-    events::{ParticipantPaid, VaultCompleted},
-    states::PaymentVault,
+    states::{ParticipantVault, PaymentVault},
+    utils::record_contribution,
 };
 
 pub fn handler(ctx: Context<Pay>, payment_id: u32, amount: u64) -> Result<()> {
-    let vault = &mut ctx.accounts.vault;
-    let vault_authority_lamports = ctx.accounts.vault_authority.lamports();
-    let amount_lamports = amount * LAMPORTS_PER_SOL;
-    let remaining_amount_lamports = vault.total_amount - vault_authority_lamports;
-
-    // Check if the amount is not greater than the vault total amount
-    require!(
-        amount_lamports <= vault.total_amount,
-        DiviError::AmountIsGreaterThanVaultTotalAmount
-    );
-
-    // Check if amount in lamports is greater than the remaining amount needed
-    require!(
-        amount_lamports <= remaining_amount_lamports,
-        DiviError::AmountIsGreaterThanRemainingVaultAmount
-    );
-
     // Transfer SOL from payer to vault_authority using System Program
     anchor_lang::system_program::transfer(
         CpiContext::new(
@@ -34,26 +17,24 @@ pub fn handler(ctx: Context<Pay>, payment_id: u32, amount: u64) -> Result<()> {
                 to: ctx.accounts.vault_authority.to_account_info(),
             },
         ),
-        amount_lamports,
+        amount,
     )?;
 
-    emit!(ParticipantPaid {
-        issuer: ctx.accounts.issuer.key(),
-        payer: ctx.accounts.payer.key(),
-        payment_id,
-        bump: vault.bump,
-    });
+    // Record the payer contribution so it can be refunded
+    ctx.accounts.participant_vault.bump = ctx.bumps.participant_vault;
 
-    // Check if vault is fully funded after this transfer
-    if vault_authority_lamports + amount_lamports >= vault.total_amount {
-        vault.is_finalized = true;
+    record_contribution(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.participant_vault,
+        ctx.accounts.payer.key(),
+        amount,
+    )?;
 
-        emit!(VaultCompleted {
-            issuer: ctx.accounts.issuer.key(),
-            payment_id,
-            bump: vault.bump,
-        });
-    }
+    msg!(
+        "Payment {} paid by {}",
+        payment_id,
+        ctx.accounts.payer.key()
+    );
 
     Ok(())
 }
@@ -79,7 +60,7 @@ pub struct Pay<'info> {
             &payment_id.to_le_bytes(),
         ],
         bump,
-        constraint = !vault.is_finalized @ DiviError::VaultIsAlreadyFinalized,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
     )]
     pub vault: Account<'info, PaymentVault>,
 
@@ -95,5 +76,19 @@ pub struct Pay<'info> {
     )]
     pub vault_authority: UncheckedAccount<'info>,
 
+    /// CHECK: Payer contribution record
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + ParticipantVault::INIT_SPACE,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
     pub system_program: Program<'info, System>,
 }
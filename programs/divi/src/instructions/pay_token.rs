@@ -0,0 +1,121 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+use crate::{
+    constants::{ANCHOR_DISCRIMINATOR, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    states::{ParticipantVault, PaymentVault},
+    utils::{record_contribution, transfer_tokens},
+};
+
+pub fn handler(ctx: Context<PayToken>, payment_id: u32, amount: u64) -> Result<()> {
+    // Transfer tokens from payer to the vault token account
+    let received_amount = transfer_tokens(
+        &ctx.accounts.payer_token_account,
+        &mut ctx.accounts.vault_token_account,
+        &ctx.accounts.payer.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        amount,
+        &[],
+    )?;
+
+    // Record the amount credited to the vault, transfer fees excluded
+    ctx.accounts.participant_vault.bump = ctx.bumps.participant_vault;
+
+    record_contribution(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.participant_vault,
+        ctx.accounts.payer.key(),
+        received_amount,
+    )?;
+
+    msg!(
+        "Payment {} paid by {}",
+        payment_id,
+        ctx.accounts.payer.key()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(
+    payment_id: u32
+)]
+pub struct PayToken<'info> {
+    /// CHECK: Participant payer
+    #[account(mut)]
+    pub payer: Signer<'info>,
+
+    /// CHECK: Payment issuer
+    pub issuer: AccountInfo<'info>,
+
+    /// CHECK: Payment vault account for metadata
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.mint == Some(mint.key()) @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: This is a PDA that owns the vault token account
+    #[account(
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: Payer token account
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = payer,
+        token::token_program = token_program,
+    )]
+    pub payer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Vault token account that will hold the funds
+    #[account(
+        init_if_needed,
+        payer = payer,
+        associated_token::mint = mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: Payer contribution record
+    #[account(
+        init_if_needed,
+        payer = payer,
+        space = ANCHOR_DISCRIMINATOR + ParticipantVault::INIT_SPACE,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            payer.key().as_ref(),
+        ],
+        bump,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
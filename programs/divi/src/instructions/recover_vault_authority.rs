@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::VaultAuthorityRecovered,
+};
+
+/**
+* Drain the lamports left in the vault authority of a closed vault back to the issuer, nothing else
+* can sign for the authority once its vault is gone
+*/
+pub fn handler(ctx: Context<RecoverVaultAuthority>, payment_id: u32) -> Result<()> {
+    let issuer_key = ctx.accounts.issuer.key();
+
+    // Get the PDA signer seeds for vault_authority
+    let authority_seeds = &[
+        VAULT_AUTHORITY.as_bytes(),
+        issuer_key.as_ref(),
+        &payment_id.to_le_bytes(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    let vault_authority = &ctx.accounts.vault_authority;
+    let transfer_amount = vault_authority.lamports();
+
+    require!(transfer_amount > 0, DiviError::InsufficientVaultBalance);
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            ctx.accounts.system_program.to_account_info(),
+            anchor_lang::system_program::Transfer {
+                from: vault_authority.to_account_info(),
+                to: ctx.accounts.issuer.to_account_info(),
+            },
+            signer_seeds,
+        ),
+        transfer_amount,
+    )?;
+
+    emit!(VaultAuthorityRecovered {
+        version: EVENT_VERSION,
+        issuer: issuer_key,
+        payment_id,
+        bump: ctx.bumps.vault,
+        mint: None,
+        amount: transfer_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Recovered {} lamports from vault authority of payment {}",
+        transfer_amount,
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct RecoverVaultAuthority<'info> {
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+        constraint = vault.data_is_empty() @ DiviError::VaultIsNotClosed,
+    )]
+    /// CHECK: Closed payment vault, only its address is checked
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    /// CHECK: PDA for vault authority
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,129 @@
+use crate::{
+    constants::{EVENT_VERSION, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::VaultAuthorityRecovered,
+    utils::{close_token_account, transfer_tokens},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    associated_token::AssociatedToken,
+    token_interface::{Mint, TokenAccount, TokenInterface},
+};
+
+/**
+* Drain the tokens left in the vault token account of a closed vault back to the issuer and close
+* it, nothing else can sign for the vault authority once its vault is gone
+*/
+pub fn handler(ctx: Context<RecoverVaultAuthorityToken>, payment_id: u32) -> Result<()> {
+    let issuer_key = ctx.accounts.issuer.key();
+
+    // Get the PDA signer seeds for vault_authority
+    let authority_seeds = &[
+        VAULT_AUTHORITY.as_bytes(),
+        issuer_key.as_ref(),
+        &payment_id.to_le_bytes(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    let transfer_amount = ctx.accounts.vault_token_account.amount;
+
+    if transfer_amount > 0 {
+        transfer_tokens(
+            &ctx.accounts.vault_token_account,
+            &mut ctx.accounts.issuer_token_account,
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            transfer_amount,
+            signer_seeds,
+        )?;
+
+        msg!(
+            "Recovered {} tokens from vault authority of payment {}",
+            transfer_amount,
+            payment_id
+        );
+    }
+
+    // Close the vault token account and send the rent to the issuer
+    close_token_account(
+        &ctx.accounts.vault_token_account,
+        &ctx.accounts.issuer.to_account_info(),
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        signer_seeds,
+    )?;
+
+    emit!(VaultAuthorityRecovered {
+        version: EVENT_VERSION,
+        issuer: issuer_key,
+        payment_id,
+        bump: ctx.bumps.vault,
+        mint: Some(ctx.accounts.mint.key()),
+        amount: transfer_amount,
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct RecoverVaultAuthorityToken<'info> {
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    #[account(
+      seeds = [
+          VAULT.as_bytes(),
+          issuer.key().as_ref(),
+          &payment_id.to_le_bytes(),
+      ],
+      bump,
+      constraint = vault.data_is_empty() @ DiviError::VaultIsNotClosed,
+  )]
+    /// CHECK: Closed payment vault, only its address is checked
+    pub vault: UncheckedAccount<'info>,
+
+    #[account(
+      seeds = [
+          VAULT_AUTHORITY.as_bytes(),
+          issuer.key().as_ref(),
+          &payment_id.to_le_bytes(),
+      ],
+      bump,
+  )]
+    /// CHECK: This is a PDA that owns the funds
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// Token mint, receives the transfer fees withheld by the vault token account before it closes
+    #[account(mut)]
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// Vault token account left behind by the closed vault
+    #[account(
+      mut,
+      associated_token::mint = mint,
+      associated_token::authority = vault_authority,
+      associated_token::token_program = token_program,
+  )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// Issuer token account receiving the tokens
+    #[account(
+      init_if_needed,
+      payer = issuer,
+      associated_token::mint = mint,
+      associated_token::authority = issuer,
+      associated_token::token_program = token_program,
+  )]
+    pub issuer_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub associated_token_program: Program<'info, AssociatedToken>,
+
+    pub system_program: Program<'info, System>,
+}
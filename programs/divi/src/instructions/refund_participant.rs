@@ -1,51 +1,45 @@
 use crate::{
-    constants::{PARTICIPANT_VAULT, PARTICIPANT_VAULT_AUTHORITY, VAULT},
+    constants::{EVENT_VERSION, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
     errors::DiviError,
     events::ParticipantRefunded,
-    states::{ParticipantVault, PaymentVault},
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+    utils::transfer_from_vault,
 };
 use anchor_lang::prelude::*;
 
 pub fn handler(ctx: Context<RefundParticipant>, payment_id: u32) -> Result<()> {
-    let vault = &ctx.accounts.vault;
     let participant_key = ctx.accounts.participant.key();
 
-    // Check if main payment vault is not finalized
-    require!(!vault.is_finalized, DiviError::PaymentAlreadyFinalized);
-
-    // Prepare seeds for the vault authority
-    let authority_seeds = &[
-        PARTICIPANT_VAULT_AUTHORITY.as_bytes(),
-        participant_key.as_ref(),
-        &payment_id.to_le_bytes(),
-        &[ctx.bumps.participant_vault_authority],
-    ];
-    let signer_seeds = &[&authority_seeds[..]];
-
-    // Transfer participant vault authority funds to participant
-    let vault_balance = ctx.accounts.participant_vault_authority.lamports();
-
-    if vault_balance > 0 {
-        let ix = anchor_lang::solana_program::system_instruction::transfer(
-            &ctx.accounts.participant_vault_authority.key(),
-            &ctx.accounts.participant.key(),
-            vault_balance,
-        );
-
-        anchor_lang::solana_program::program::invoke_signed(
-            &ix,
-            &[
-                ctx.accounts.participant_vault_authority.to_account_info(),
-                ctx.accounts.participant.to_account_info(),
-                ctx.accounts.system_program.to_account_info(),
-            ],
-            signer_seeds,
+    // Check if main payment vault is not funded, a funded vault keeps the contributions
+    ctx.accounts.vault.require_status(&[
+        VaultStatus::Open,
+        VaultStatus::Cancelled,
+        VaultStatus::Expired,
+    ])?;
+
+    // Transfer the participant contribution back from the vault authority
+    let amount = ctx
+        .accounts
+        .vault
+        .release_contribution(participant_key, ctx.accounts.participant_vault.amount)?;
+    ctx.accounts
+        .vault
+        .remove_participant(ctx.accounts.participant_vault.approved_milestones)?;
+
+    if amount > 0 {
+        transfer_from_vault(
+            &ctx.accounts.vault,
+            &ctx.accounts.vault_authority.to_account_info(),
+            ctx.bumps.vault_authority,
+            &ctx.accounts.participant.to_account_info(),
+            &ctx.accounts.system_program.to_account_info(),
+            amount,
         )?;
 
         msg!(
             "Refunded {} lamports to participant {}",
-            vault_balance,
-            ctx.accounts.participant.key()
+            amount,
+            participant_key
         );
     }
 
@@ -55,10 +49,15 @@ pub fn handler(ctx: Context<RefundParticipant>, payment_id: u32) -> Result<()> {
         .close(ctx.accounts.participant.to_account_info())?;
 
     emit!(ParticipantRefunded {
-        issuer: ctx.accounts.issuer.key().clone(),
-        payment_id: payment_id.clone(),
-        bump: vault.bump.clone(),
-        participant: participant_key.clone()
+        version: EVENT_VERSION,
+        issuer: ctx.accounts.issuer.key(),
+        payment_id,
+        bump: ctx.accounts.vault.bump,
+        participant: participant_key,
+        amount,
+        collected_amount: ctx.accounts.vault.collected_amount,
+        remaining_amount: ctx.accounts.vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
     });
 
     msg!(
@@ -85,41 +84,44 @@ pub struct RefundParticipant<'info> {
 
     /// CHECK: The main payment vault
     #[account(
+        mut,
         seeds = [
             VAULT.as_bytes(),
             vault.issuer.as_ref(),
             &payment_id.to_le_bytes(),
         ],
         bump = vault.bump,
-        constraint = !vault.is_finalized @ DiviError::PaymentAlreadyFinalized,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
     )]
     pub vault: Account<'info, PaymentVault>,
 
-    /// CHECK: The participant vault
+    /// CHECK: The main vault authority (funds)
     #[account(
         mut,
         seeds = [
-            PARTICIPANT_VAULT.as_bytes(),
-            participant.key().as_ref(),
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
             &payment_id.to_le_bytes(),
         ],
         bump,
-        constraint = participant_vault.participant == participant.key() @ DiviError::InvalidParticipant,
-        constraint = participant_vault.payment_id == payment_id @ DiviError::InvalidPaymentId,
     )]
-    pub participant_vault: Account<'info, ParticipantVault>,
+    pub vault_authority: UncheckedAccount<'info>,
 
-    /// CHECK: The participant vault authority (funds)
+    /// CHECK: The participant vault
     #[account(
         mut,
         seeds = [
-            PARTICIPANT_VAULT_AUTHORITY.as_bytes(),
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
             participant.key().as_ref(),
-            &payment_id.to_le_bytes(),
         ],
         bump,
+        constraint = participant_vault.participant == participant.key() @ DiviError::InvalidParticipant,
+        constraint = participant_vault.payment_id == payment_id @ DiviError::InvalidPaymentId,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
     )]
-    pub participant_vault_authority: UncheckedAccount<'info>,
+    pub participant_vault: Account<'info, ParticipantVault>,
 
     pub system_program: Program<'info, System>,
 }
@@ -0,0 +1,160 @@
+use crate::{
+    constants::{EVENT_VERSION, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::ParticipantRefunded,
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+    utils::transfer_tokens,
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+pub fn handler(ctx: Context<RefundParticipantToken>, payment_id: u32) -> Result<()> {
+    let participant_key = ctx.accounts.participant.key();
+    let issuer_key = ctx.accounts.vault.issuer;
+
+    // Check if main payment vault is not funded, a funded vault keeps the contributions
+    ctx.accounts.vault.require_status(&[
+        VaultStatus::Open,
+        VaultStatus::Cancelled,
+        VaultStatus::Expired,
+    ])?;
+
+    // Prepare seeds for the vault authority
+    let authority_seeds = &[
+        VAULT_AUTHORITY.as_bytes(),
+        issuer_key.as_ref(),
+        &payment_id.to_le_bytes(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // Transfer the participant contribution back from the vault token account
+    let amount = ctx
+        .accounts
+        .vault
+        .release_contribution(participant_key, ctx.accounts.participant_vault.amount)?;
+    ctx.accounts
+        .vault
+        .remove_participant(ctx.accounts.participant_vault.approved_milestones)?;
+
+    if amount > 0 {
+        transfer_tokens(
+            &ctx.accounts.vault_token_account,
+            &mut ctx.accounts.participant_token_account,
+            &ctx.accounts.vault_authority.to_account_info(),
+            &ctx.accounts.mint,
+            &ctx.accounts.token_program,
+            amount,
+            signer_seeds,
+        )?;
+
+        msg!(
+            "Refunded {} tokens to participant {}",
+            amount,
+            participant_key
+        );
+    }
+
+    // Close the participant vault and send the rent to the participant
+    ctx.accounts
+        .participant_vault
+        .close(ctx.accounts.participant.to_account_info())?;
+
+    emit!(ParticipantRefunded {
+        version: EVENT_VERSION,
+        issuer: ctx.accounts.issuer.key(),
+        payment_id,
+        bump: ctx.accounts.vault.bump,
+        participant: participant_key,
+        amount,
+        collected_amount: ctx.accounts.vault.collected_amount,
+        remaining_amount: ctx.accounts.vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Successfully closed participant vault for {}",
+        participant_key
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct RefundParticipantToken<'info> {
+    /// CHECK: The main payment vault issuer
+    #[account(
+        mut,
+        constraint = issuer.key() == vault.issuer @ DiviError::InvalidVaultAuthority
+    )]
+    pub issuer: Signer<'info>,
+
+    /// CHECK: This is the participant receiving funds
+    #[account(mut)]
+    pub participant: UncheckedAccount<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.mint == Some(mint.key()) @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The main vault authority, owner of the vault token account
+    #[account(
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The participant vault
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump,
+        constraint = participant_vault.participant == participant.key() @ DiviError::InvalidParticipant,
+        constraint = participant_vault.payment_id == payment_id @ DiviError::InvalidPaymentId,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: The vault token account (funds)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The participant token account receiving funds
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = participant,
+        token::token_program = token_program,
+    )]
+    pub participant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,154 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::ParticipantRefunded,
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+    utils::transfer_from_vault,
+};
+
+/**
+* Refund many participants at once, the remaining accounts are (participant, participant vault)
+* pairs. Each participant vault is checked against its seeds, refunded and closed like
+* `refund_participant` does
+*/
+pub fn handler<'info>(
+    ctx: Context<'_, '_, 'info, 'info, RefundParticipantsBatch<'info>>,
+    payment_id: u32,
+) -> Result<()> {
+    let vault_key = ctx.accounts.vault.key();
+
+    // Check if main payment vault is not funded, a funded vault keeps the contributions
+    ctx.accounts.vault.require_status(&[
+        VaultStatus::Open,
+        VaultStatus::Cancelled,
+        VaultStatus::Expired,
+    ])?;
+
+    // Check if the remaining accounts are (participant, participant vault) pairs
+    let pairs = ctx.remaining_accounts.chunks_exact(2);
+    require!(
+        pairs.len() > 0 && pairs.remainder().is_empty(),
+        DiviError::InvalidRefundBatch
+    );
+
+    for accounts in pairs {
+        let participant = &accounts[0];
+        let participant_vault_info = &accounts[1];
+
+        require!(
+            participant.is_writable && participant_vault_info.is_writable,
+            DiviError::InvalidRefundBatch
+        );
+
+        // Check the participant vault is owned by the program and derived from this vault
+        let participant_vault = Account::<ParticipantVault>::try_from(participant_vault_info)?;
+        let expected_key = Pubkey::create_program_address(
+            &[
+                PARTICIPANT_VAULT.as_bytes(),
+                vault_key.as_ref(),
+                participant.key.as_ref(),
+                &[participant_vault.bump],
+            ],
+            ctx.program_id,
+        )
+        .map_err(|_| DiviError::InvalidParticipantVault)?;
+
+        require_keys_eq!(
+            expected_key,
+            participant_vault_info.key(),
+            DiviError::InvalidParticipantVault
+        );
+        require_keys_eq!(
+            participant_vault.participant,
+            participant.key(),
+            DiviError::InvalidParticipant
+        );
+        require!(
+            participant_vault.vault_nonce == ctx.accounts.vault.nonce,
+            DiviError::InvalidParticipantVault
+        );
+
+        // Transfer the participant contribution back from the vault authority
+        let amount = ctx
+            .accounts
+            .vault
+            .release_contribution(participant.key(), participant_vault.amount)?;
+        ctx.accounts
+            .vault
+            .remove_participant(participant_vault.approved_milestones)?;
+
+        if amount > 0 {
+            transfer_from_vault(
+                &ctx.accounts.vault,
+                &ctx.accounts.vault_authority.to_account_info(),
+                ctx.bumps.vault_authority,
+                participant,
+                &ctx.accounts.system_program.to_account_info(),
+                amount,
+            )?;
+        }
+
+        // Close the participant vault and send the rent to the participant
+        participant_vault.close(participant.clone())?;
+
+        emit!(ParticipantRefunded {
+            version: EVENT_VERSION,
+            issuer: ctx.accounts.issuer.key(),
+            payment_id,
+            bump: ctx.accounts.vault.bump,
+            participant: participant.key(),
+            amount,
+            collected_amount: ctx.accounts.vault.collected_amount,
+            remaining_amount: ctx.accounts.vault.remaining_amount(),
+            timestamp: Clock::get()?.unix_timestamp,
+        });
+
+        msg!(
+            "Refunded {} lamports to participant {}",
+            amount,
+            participant.key
+        );
+    }
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct RefundParticipantsBatch<'info> {
+    /// CHECK: The main payment vault issuer
+    #[account(
+        mut,
+        constraint = issuer.key() == vault.issuer @ DiviError::InvalidVaultAuthority
+    )]
+    pub issuer: Signer<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The main vault authority (funds)
+    #[account(
+        mut,
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
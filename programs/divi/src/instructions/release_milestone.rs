@@ -0,0 +1,124 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    events::MilestoneReleased,
+    states::{PaymentVault, VaultStatus},
+    utils::transfer_from_vault,
+};
+
+/**
+* Release a milestone amount to the recipient, the rest of the vault stays refundable. The vault
+* must be funded, a contributor joining after a release would only get his refund pro-rata, and
+* its release quorum reached if any
+*/
+pub fn handler(ctx: Context<ReleaseMilestone>, payment_id: u32, index: u8) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    vault.require_status(&[VaultStatus::Funded])?;
+
+    // Check if the vault deadline is not over, contributors can claim their refund after it
+    require!(
+        !vault.is_expired(Clock::get()?.unix_timestamp),
+        DiviError::VaultIsExpired
+    );
+
+    let escrowed_amount = vault.escrowed_amount()?;
+    let milestone = vault
+        .milestones
+        .get_mut(index as usize)
+        .ok_or(DiviError::MilestoneNotFound)?;
+
+    require!(!milestone.is_released, DiviError::MilestoneAlreadyReleased);
+    require!(
+        milestone.approvals >= milestone.approvals_required,
+        DiviError::MilestoneNotApproved
+    );
+    require!(
+        milestone.amount <= escrowed_amount,
+        DiviError::InsufficientVaultBalance
+    );
+
+    milestone.is_released = true;
+    let amount = milestone.amount;
+
+    vault.released_amount = vault
+        .released_amount
+        .checked_add(amount)
+        .ok_or(DiviError::ArithmeticOverflow)?;
+
+    // Transfer the milestone amount from the vault authority to the recipient
+    transfer_from_vault(
+        vault,
+        &ctx.accounts.vault_authority.to_account_info(),
+        ctx.bumps.vault_authority,
+        &ctx.accounts.recipient.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        amount,
+    )?;
+
+    emit!(MilestoneReleased {
+        version: EVENT_VERSION,
+        issuer: vault.issuer,
+        payment_id,
+        bump: vault.bump,
+        index,
+        amount,
+        collected_amount: vault.collected_amount,
+        released_amount: vault.released_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!(
+        "Released {} lamports of payment {} for milestone {}",
+        amount,
+        payment_id,
+        index
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct ReleaseMilestone<'info> {
+    pub issuer: Signer<'info>,
+
+    /// CHECK: Recipient who receives the funds, checked against the vault recipient
+    #[account(
+        mut,
+        address = vault.recipient @ DiviError::InvalidRecipient,
+    )]
+    pub recipient: UncheckedAccount<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+        constraint = vault.payout_recipients.is_empty() @ DiviError::PayoutRecipientsConfigured,
+        constraint = vault.is_release_approved() @ DiviError::ReleaseNotApproved,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: Vault authority - a PDA that holds the SOL funds
+    #[account(
+        mut,
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,88 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{EVENT_VERSION, VAULT},
+    errors::DiviError,
+    events::{DisputeResolved, VaultCancelled},
+    states::{DisputeResolution, PaymentVault, VaultStatus},
+};
+
+/**
+* Let the arbiter settle a dispute, either by releasing the funds or by cancelling the payment so
+* contributors claim their refund pro-rata of the escrowed amount
+*/
+pub fn handler(
+    ctx: Context<ResolveDispute>,
+    payment_id: u32,
+    resolution: DisputeResolution,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    // The ruling is final, contributors can't dispute the vault again
+    vault.is_dispute_resolved = true;
+    vault.dispute_deadline = None;
+
+    match resolution {
+        DisputeResolution::Release => {
+            // The arbiter decision replaces the contributor approvals
+            vault.release_quorum = None;
+            vault.release_deadline = None;
+
+            if vault.collected_amount >= vault.total_amount {
+                vault.transition(VaultStatus::Funded)?;
+            } else {
+                vault.transition(VaultStatus::Open)?;
+            }
+        }
+        DisputeResolution::Refund => {
+            vault.transition(VaultStatus::Cancelled)?;
+
+            emit!(VaultCancelled {
+                version: EVENT_VERSION,
+                issuer: vault.issuer,
+                payment_id,
+                bump: vault.bump,
+                amount: vault.escrowed_amount()?,
+                collected_amount: vault.collected_amount,
+                remaining_amount: vault.remaining_amount(),
+                timestamp: Clock::get()?.unix_timestamp,
+            });
+        }
+    }
+
+    emit!(DisputeResolved {
+        version: EVENT_VERSION,
+        issuer: vault.issuer,
+        payment_id,
+        bump: vault.bump,
+        arbiter: ctx.accounts.arbiter.key(),
+        resolution,
+        amount: vault.escrowed_amount()?,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    msg!("Payment {} dispute resolved by the arbiter", payment_id);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct ResolveDispute<'info> {
+    pub arbiter: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.arbiter == Some(arbiter.key()) @ DiviError::InvalidArbiter,
+        constraint = vault.status == VaultStatus::Disputed @ DiviError::VaultIsNotDisputed,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+}
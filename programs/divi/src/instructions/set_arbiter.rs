@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{DEFAULT_DISPUTE_WINDOW, VAULT},
+    errors::DiviError,
+    states::PaymentVault,
+};
+
+/**
+* Appoint the neutral party resolving disputes and the time it has to resolve one, until the
+* vault receives a contribution
+*/
+pub fn handler(
+    ctx: Context<SetArbiter>,
+    payment_id: u32,
+    arbiter: Option<Pubkey>,
+    dispute_window: Option<i64>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    // Check if the arbiter is not the issuer
+    require!(arbiter != Some(vault.issuer), DiviError::InvalidArbiter);
+
+    // Check if the arbiter gets some time to resolve a dispute
+    let dispute_window = dispute_window.unwrap_or(DEFAULT_DISPUTE_WINDOW);
+    require!(dispute_window > 0, DiviError::InvalidDisputeWindow);
+
+    vault.arbiter = arbiter;
+    vault.dispute_window = dispute_window;
+
+    msg!("Payment {} arbiter set to {:?}", payment_id, arbiter);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct SetArbiter<'info> {
+    pub issuer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+        constraint = !vault.has_contributions @ DiviError::InvalidArbiter,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+}
@@ -0,0 +1,69 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{ANCHOR_DISCRIMINATOR, EVENT_VERSION, PAYMENT_METADATA, VAULT},
+    errors::DiviError,
+    events::MetadataUpdated,
+    states::{PaymentMetadata, PaymentMetadataArgs, PaymentVault},
+};
+
+/**
+* Create or replace the metadata describing the payment, until the vault receives a contribution
+*/
+pub fn handler(
+    ctx: Context<SetMetadata>,
+    payment_id: u32,
+    metadata: PaymentMetadataArgs,
+) -> Result<()> {
+    let payment_metadata = &mut ctx.accounts.metadata;
+
+    payment_metadata.vault = ctx.accounts.vault.key();
+    payment_metadata.bump = ctx.bumps.metadata;
+    payment_metadata.update(metadata.clone())?;
+
+    emit!(MetadataUpdated {
+        version: EVENT_VERSION,
+        issuer: ctx.accounts.issuer.key(),
+        payment_id,
+        bump: ctx.accounts.vault.bump,
+        amount: ctx.accounts.vault.total_amount,
+        collected_amount: ctx.accounts.vault.collected_amount,
+        remaining_amount: ctx.accounts.vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+        metadata,
+    });
+
+    msg!("Payment {} metadata updated", payment_id);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct SetMetadata<'info> {
+    #[account(mut)]
+    pub issuer: Signer<'info>,
+
+    #[account(
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+        constraint = !vault.has_contributions @ DiviError::MetadataIsLocked,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    #[account(
+        init_if_needed,
+        payer = issuer,
+        space = ANCHOR_DISCRIMINATOR + PaymentMetadata::INIT_SPACE,
+        seeds = [PAYMENT_METADATA.as_bytes(), vault.key().as_ref()],
+        bump,
+    )]
+    pub metadata: Account<'info, PaymentMetadata>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,77 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{MAX_MILESTONES, VAULT},
+    errors::DiviError,
+    states::{Milestone, MilestoneArgs, PaymentVault},
+};
+
+/**
+* Define the partial releases of the vault, until the vault receives a contribution
+*/
+pub fn handler(
+    ctx: Context<SetMilestones>,
+    payment_id: u32,
+    milestones: Vec<MilestoneArgs>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    // Check if the milestones don't release more than the total amount
+    require!(
+        milestones.len() <= MAX_MILESTONES,
+        DiviError::InvalidMilestones
+    );
+
+    let mut milestones_total: u64 = 0;
+
+    for milestone in milestones.iter() {
+        require!(milestone.amount > 0, DiviError::InvalidMilestones);
+
+        milestones_total = milestones_total
+            .checked_add(milestone.amount)
+            .ok_or(DiviError::ArithmeticOverflow)?;
+    }
+
+    require!(
+        milestones_total <= vault.total_amount,
+        DiviError::InvalidMilestones
+    );
+
+    vault.milestones = milestones
+        .into_iter()
+        .map(|milestone| Milestone {
+            amount: milestone.amount,
+            approvals_required: milestone.approvals_required,
+            approvals: 0,
+            is_released: false,
+        })
+        .collect();
+
+    msg!(
+        "Payment {} released in {} milestones",
+        payment_id,
+        vault.milestones.len()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct SetMilestones<'info> {
+    pub issuer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+        constraint = !vault.has_contributions @ DiviError::InvalidMilestones,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+}
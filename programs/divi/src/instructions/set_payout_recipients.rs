@@ -0,0 +1,74 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BASIS_POINTS, MAX_PAYOUT_RECIPIENTS, VAULT},
+    errors::DiviError,
+    states::{PaymentVault, PayoutRecipient},
+};
+
+/**
+* Share the collected amount between several recipients, until the vault receives a contribution.
+* An empty list goes back to paying the single vault recipient
+*/
+pub fn handler(
+    ctx: Context<SetPayoutRecipients>,
+    payment_id: u32,
+    payout_recipients: Vec<PayoutRecipient>,
+) -> Result<()> {
+    // Check if the recipients are unique and their basis points add up to 10 000
+    if !payout_recipients.is_empty() {
+        require!(
+            payout_recipients.len() <= MAX_PAYOUT_RECIPIENTS,
+            DiviError::InvalidPayoutRecipients
+        );
+
+        let mut basis_points_total: u64 = 0;
+
+        for (index, payout) in payout_recipients.iter().enumerate() {
+            require!(
+                payout.basis_points > 0
+                    && !payout_recipients[..index]
+                        .iter()
+                        .any(|other| other.recipient == payout.recipient),
+                DiviError::InvalidPayoutRecipients
+            );
+
+            basis_points_total += payout.basis_points as u64;
+        }
+
+        require!(
+            basis_points_total == BASIS_POINTS,
+            DiviError::InvalidPayoutRecipients
+        );
+    }
+
+    ctx.accounts.vault.payout_recipients = payout_recipients;
+
+    msg!(
+        "Payment {} split between {} recipients",
+        payment_id,
+        ctx.accounts.vault.payout_recipients.len()
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct SetPayoutRecipients<'info> {
+    pub issuer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+        constraint = !vault.has_contributions @ DiviError::RecipientIsLocked,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+}
@@ -0,0 +1,35 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::VAULT, errors::DiviError, states::PaymentVault};
+
+/**
+* Change the wallet receiving the collected amount, until the vault receives a contribution
+*/
+pub fn handler(ctx: Context<SetRecipient>, payment_id: u32, recipient: Pubkey) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    vault.recipient = recipient;
+
+    msg!("Payment {} recipient set to {}", payment_id, recipient);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct SetRecipient<'info> {
+    pub issuer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+        constraint = !vault.has_contributions @ DiviError::RecipientIsLocked,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+}
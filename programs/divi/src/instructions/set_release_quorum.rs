@@ -0,0 +1,72 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BASIS_POINTS, VAULT},
+    errors::DiviError,
+    states::{PaymentVault, ReleaseQuorum},
+};
+
+/**
+* Keep the funds locked once the vault is funded until a quorum of contributors approves the
+* release, until the vault receives a contribution
+*/
+pub fn handler(
+    ctx: Context<SetReleaseQuorum>,
+    payment_id: u32,
+    release_quorum: Option<ReleaseQuorum>,
+    release_deadline: Option<i64>,
+) -> Result<()> {
+    let vault = &mut ctx.accounts.vault;
+
+    // Check if the quorum can be reached
+    match release_quorum {
+        Some(ReleaseQuorum::Contributors { approvals }) => {
+            require!(approvals > 0, DiviError::InvalidReleaseQuorum)
+        }
+        Some(ReleaseQuorum::Amount { basis_points }) => require!(
+            basis_points > 0 && basis_points as u64 <= BASIS_POINTS,
+            DiviError::InvalidReleaseQuorum
+        ),
+        None => {}
+    }
+
+    // Check if a quorum comes with a deadline, so the funds can't stay locked forever
+    require!(
+        release_quorum.is_some() == release_deadline.is_some(),
+        DiviError::InvalidReleaseQuorum
+    );
+
+    // Check if the deadline is in the future
+    if let Some(release_deadline) = release_deadline {
+        require!(
+            release_deadline > Clock::get()?.unix_timestamp,
+            DiviError::InvalidExpiration
+        );
+    }
+
+    vault.release_quorum = release_quorum;
+    vault.release_deadline = release_deadline;
+
+    msg!("Payment {} release quorum updated", payment_id);
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct SetReleaseQuorum<'info> {
+    pub issuer: Signer<'info>,
+
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            issuer.key().as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.issuer == issuer.key() @ DiviError::InvalidVaultAuthority,
+        constraint = !vault.has_contributions @ DiviError::InvalidReleaseQuorum,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+}
@@ -0,0 +1,98 @@
+use crate::{
+    constants::{PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+    utils::{transfer_from_vault, withdraw_contribution},
+};
+use anchor_lang::prelude::*;
+
+/**
+* Let a participant take back part or all of his contribution while the vault is still open
+*/
+pub fn handler(ctx: Context<WithdrawParticipation>, payment_id: u32, amount: u64) -> Result<()> {
+    let participant_key = ctx.accounts.participant.key();
+
+    // Check if the vault still accepts contributions, a funded vault keeps them
+    ctx.accounts.vault.require_status(&[VaultStatus::Open])?;
+
+    let refund_amount = withdraw_contribution(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.participant_vault,
+        amount,
+    )?;
+
+    // Transfer the withdrawn amount left in the vault back from the vault authority
+    transfer_from_vault(
+        &ctx.accounts.vault,
+        &ctx.accounts.vault_authority.to_account_info(),
+        ctx.bumps.vault_authority,
+        &ctx.accounts.participant.to_account_info(),
+        &ctx.accounts.system_program.to_account_info(),
+        refund_amount,
+    )?;
+
+    // Close the participant vault once the whole contribution is withdrawn
+    if ctx.accounts.participant_vault.amount == 0 {
+        ctx.accounts
+            .participant_vault
+            .close(ctx.accounts.participant.to_account_info())?;
+    }
+
+    msg!(
+        "Participant {} withdrew {} lamports from payment {}",
+        participant_key,
+        refund_amount,
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct WithdrawParticipation<'info> {
+    /// CHECK: The participant withdrawing his contribution
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.mint.is_none() @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The main vault authority (funds)
+    #[account(
+        mut,
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The participant vault
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    pub system_program: Program<'info, System>,
+}
@@ -0,0 +1,133 @@
+use crate::{
+    constants::{PARTICIPANT_VAULT, VAULT, VAULT_AUTHORITY},
+    errors::DiviError,
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+    utils::{transfer_tokens, withdraw_contribution},
+};
+use anchor_lang::prelude::*;
+use anchor_spl::token_interface::{Mint, TokenAccount, TokenInterface};
+
+/**
+* Let a participant take back part or all of his tokens while the vault is still open
+*/
+pub fn handler(
+    ctx: Context<WithdrawParticipationToken>,
+    payment_id: u32,
+    amount: u64,
+) -> Result<()> {
+    let participant_key = ctx.accounts.participant.key();
+    let issuer_key = ctx.accounts.vault.issuer;
+
+    // Check if the vault still accepts contributions, a funded vault keeps them
+    ctx.accounts.vault.require_status(&[VaultStatus::Open])?;
+
+    let refund_amount = withdraw_contribution(
+        &mut ctx.accounts.vault,
+        &mut ctx.accounts.participant_vault,
+        amount,
+    )?;
+
+    // Prepare seeds for the vault authority
+    let authority_seeds = &[
+        VAULT_AUTHORITY.as_bytes(),
+        issuer_key.as_ref(),
+        &payment_id.to_le_bytes(),
+        &[ctx.bumps.vault_authority],
+    ];
+    let signer_seeds = &[&authority_seeds[..]];
+
+    // Transfer the withdrawn amount left in the vault back from the vault token account
+    transfer_tokens(
+        &ctx.accounts.vault_token_account,
+        &mut ctx.accounts.participant_token_account,
+        &ctx.accounts.vault_authority.to_account_info(),
+        &ctx.accounts.mint,
+        &ctx.accounts.token_program,
+        refund_amount,
+        signer_seeds,
+    )?;
+
+    // Close the participant vault once the whole contribution is withdrawn
+    if ctx.accounts.participant_vault.amount == 0 {
+        ctx.accounts
+            .participant_vault
+            .close(ctx.accounts.participant.to_account_info())?;
+    }
+
+    msg!(
+        "Participant {} withdrew {} tokens from payment {}",
+        participant_key,
+        refund_amount,
+        payment_id
+    );
+
+    Ok(())
+}
+
+#[derive(Accounts)]
+#[instruction(payment_id: u32)]
+pub struct WithdrawParticipationToken<'info> {
+    /// CHECK: The participant withdrawing his contribution
+    #[account(mut)]
+    pub participant: Signer<'info>,
+
+    /// CHECK: The main payment vault
+    #[account(
+        mut,
+        seeds = [
+            VAULT.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump = vault.bump,
+        constraint = vault.mint == Some(mint.key()) @ DiviError::MintMismatch,
+    )]
+    pub vault: Account<'info, PaymentVault>,
+
+    /// CHECK: The main vault authority, owner of the vault token account
+    #[account(
+        seeds = [
+            VAULT_AUTHORITY.as_bytes(),
+            vault.issuer.as_ref(),
+            &payment_id.to_le_bytes(),
+        ],
+        bump,
+    )]
+    pub vault_authority: UncheckedAccount<'info>,
+
+    /// CHECK: The participant vault
+    #[account(
+        mut,
+        seeds = [
+            PARTICIPANT_VAULT.as_bytes(),
+            vault.key().as_ref(),
+            participant.key().as_ref(),
+        ],
+        bump = participant_vault.bump,
+        constraint = participant_vault.issuer == vault.issuer @ DiviError::InvalidParticipantVault,
+        constraint = participant_vault.vault_nonce == vault.nonce @ DiviError::InvalidParticipantVault,
+    )]
+    pub participant_vault: Account<'info, ParticipantVault>,
+
+    pub mint: InterfaceAccount<'info, Mint>,
+
+    /// CHECK: The vault token account (funds)
+    #[account(
+        mut,
+        associated_token::mint = mint,
+        associated_token::authority = vault_authority,
+        associated_token::token_program = token_program,
+    )]
+    pub vault_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    /// CHECK: The participant token account receiving funds
+    #[account(
+        mut,
+        token::mint = mint,
+        token::authority = participant,
+        token::token_program = token_program,
+    )]
+    pub participant_token_account: InterfaceAccount<'info, TokenAccount>,
+
+    pub token_program: Interface<'info, TokenInterface>,
+}
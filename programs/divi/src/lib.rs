@@ -10,6 +10,10 @@ pub mod states;
 pub mod utils;
 
 use instructions::*;
+use states::{
+    DisputeResolution, InvoiceShareArgs, MilestoneArgs, PaymentMetadataArgs, PayoutRecipient,
+    ReleaseQuorum, SplitMode,
+};
 
 #[program]
 pub mod divi {
@@ -17,60 +21,86 @@ pub mod divi {
 
     /// Initialize a payment vault that hosts payment infos and primary payer
     ///
+    /// Pass a SPL Token or Token-2022 `mint` account to create a token vault, omit it for native SOL. The issuer
+    /// funds the rent of a SOL vault authority, refunded when the vault is closed
+    ///
     /// ### Parameters
-    /// - `payment_id` - Unique payment ID to find vault PDA
-    /// - `total_amount` - This is the total amount that the primary payer must pay (alone or with other participants)
+    /// - `payment_id` - Unique payment ID to find vault PDA, below `FIRST_PROFILE_PAYMENT_ID`
+    /// - `total_amount` - This is the total amount that the primary payer must pay (alone or with other participants),
+    ///   in base units of the vault asset (lamports for SOL)
+    /// - `expires_at` - Optional unix timestamp after which contributions are rejected and participants can claim a refund
+    /// - `recipient` - Optional wallet receiving the collected amount when the vault is closed, the issuer if omitted
+    /// - `split_mode` - How `total_amount` is split between the debtors: exact amounts, equally, in basis points
+    ///   or by weights. Remaining base units after rounding go to the first debtors
+    /// - `shares` - Debtors and their share value for the split mode. Leave empty for an open vault where anyone
+    ///   can contribute any amount
     pub fn initialize_vault(
         ctx: Context<InitializeVault>,
         payment_id: u32,
         total_amount: u64,
+        expires_at: Option<i64>,
+        recipient: Option<Pubkey>,
+        split_mode: SplitMode,
+        shares: Vec<InvoiceShareArgs>,
     ) -> Result<()> {
-        return instructions::initialize_vault::handler(ctx, payment_id, total_amount);
+        instructions::initialize_vault::handler(
+            ctx,
+            payment_id,
+            total_amount,
+            expires_at,
+            recipient,
+            split_mode,
+            shares,
+        )
     }
 
     /// Participant pay his share
     ///
     /// ### Parameters
     /// - `payment_id` - Unique payment ID to find vault PDA
-    /// - `amount` - Amount to transfert
+    /// - `amount` - Amount to transfert, in lamports
     pub fn pay(ctx: Context<Pay>, payment_id: u32, amount: u64) -> Result<()> {
-        return instructions::pay::handler(ctx, payment_id, amount);
+        instructions::pay::handler(ctx, payment_id, amount)
     }
 
-    /// Close the vault and transfert the lamports to the issuer
+    /// Close the funded vault, pay the collected amount out to the recipient and the rent back to
+    /// the issuer
     ///
     /// ### Parameters
     /// - `payment_id` - Unique payment ID to find vault PDA
     pub fn close_vault(ctx: Context<CloseVault>, payment_id: u32) -> Result<()> {
-        return instructions::close_vault::handler(ctx, payment_id);
+        instructions::close_vault::handler(ctx, payment_id)
     }
 
-    /// Participate to payment with vault
+    /// Participate to payment with vault, the contribution is held by the vault authority
+    /// and counts toward the vault total amount. It stays refundable until the vault is finalized
     ///
     /// ### Parameters
     /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `amount` - Amount to transfert, in lamports
     pub fn participate(
         ctx: Context<CreateParticipantVault>,
         payment_id: u32,
         amount: u64,
     ) -> Result<()> {
-        return instructions::create_participant_vault::handler(ctx, payment_id, amount);
+        instructions::create_participant_vault::handler(ctx, payment_id, amount)
     }
 
-    /// Close the vault and transfert the lamports to the issuer
+    /// Cancel the payment, participants left in the vault claim their refund
     ///
     /// ### Parameters
     /// - `payment_id` - Unique payment ID to find vault PDA
     pub fn cancel_payment(ctx: Context<CancelPayment>, payment_id: u32) -> Result<()> {
-        return instructions::cancel_payment::handler(ctx, payment_id);
+        instructions::cancel_payment::handler(ctx, payment_id)
     }
 
-    /// Close the vault and transfert the lamports to the issuer
+    /// Close a vault without participants left, refund them first. Lamports sent directly to the
+    /// vault authority are swept to the issuer
     ///
     /// ### Parameters
     /// - `payment_id` - Unique payment ID to find vault PDA
     pub fn close_payment_vault(ctx: Context<ClosePaymentVault>, payment_id: u32) -> Result<()> {
-        return instructions::close_payment_vault::handler(ctx, payment_id);
+        instructions::close_payment_vault::handler(ctx, payment_id)
     }
 
     /// Refund a payer participant
@@ -78,6 +108,365 @@ pub mod divi {
     /// ### Parameters
     /// - `payment_id` - Unique payment ID to find vault PDA
     pub fn refund_participant(ctx: Context<RefundParticipant>, payment_id: u32) -> Result<()> {
-        return instructions::refund_participant::handler(ctx, payment_id);
+        instructions::refund_participant::handler(ctx, payment_id)
+    }
+
+    /// Participant pay his share with SPL Token or Token-2022 tokens
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `amount` - Amount to transfert, in base units of the mint
+    pub fn pay_token(ctx: Context<PayToken>, payment_id: u32, amount: u64) -> Result<()> {
+        instructions::pay_token::handler(ctx, payment_id, amount)
+    }
+
+    /// Participate to a token payment with vault
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `amount` - Amount to transfert, in base units of the mint
+    pub fn participate_token(
+        ctx: Context<ParticipateToken>,
+        payment_id: u32,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::participate_token::handler(ctx, payment_id, amount)
+    }
+
+    /// Refund the tokens of a payer participant
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn refund_participant_token(
+        ctx: Context<RefundParticipantToken>,
+        payment_id: u32,
+    ) -> Result<()> {
+        instructions::refund_participant_token::handler(ctx, payment_id)
+    }
+
+    /// Close the token vault and transfert the tokens to the issuer
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn close_vault_token(ctx: Context<CloseVaultToken>, payment_id: u32) -> Result<()> {
+        instructions::close_vault_token::handler(ctx, payment_id)
+    }
+
+    /// Participant claims his own refund once the vault is cancelled or expired without being finalized, or once
+    /// its release isn't approved before the release deadline
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn claim_refund(ctx: Context<ClaimRefund>, payment_id: u32) -> Result<()> {
+        instructions::claim_refund::handler(ctx, payment_id)
+    }
+
+    /// Participant claims his own tokens back once the vault is cancelled or expired without being finalized, or
+    /// once its release isn't approved before the release deadline
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn claim_refund_token(ctx: Context<ClaimRefundToken>, payment_id: u32) -> Result<()> {
+        instructions::claim_refund_token::handler(ctx, payment_id)
+    }
+
+    /// Participant withdraws part or all of his contribution while the vault is still open
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `amount` - Amount to withdraw, in lamports. The participant vault is closed once it reaches zero
+    pub fn withdraw_participation(
+        ctx: Context<WithdrawParticipation>,
+        payment_id: u32,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_participation::handler(ctx, payment_id, amount)
+    }
+
+    /// Participant withdraws part or all of his tokens while the vault is still open
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `amount` - Amount to withdraw, in base units of the mint. The participant vault is closed once it reaches zero
+    pub fn withdraw_participation_token(
+        ctx: Context<WithdrawParticipationToken>,
+        payment_id: u32,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::withdraw_participation_token::handler(ctx, payment_id, amount)
+    }
+
+    /// Participant adds lamports to his existing participation
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `amount` - Amount to add, in lamports, capped by the vault remaining amount
+    pub fn increase_participation(
+        ctx: Context<IncreaseParticipation>,
+        payment_id: u32,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::increase_participation::handler(ctx, payment_id, amount)
+    }
+
+    /// Participant adds tokens to his existing participation
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `amount` - Amount to add, in base units of the mint, capped by the vault remaining amount
+    pub fn increase_participation_token(
+        ctx: Context<IncreaseParticipationToken>,
+        payment_id: u32,
+        amount: u64,
+    ) -> Result<()> {
+        instructions::increase_participation_token::handler(ctx, payment_id, amount)
+    }
+
+    /// Issuer changes the wallet receiving the collected amount, until the vault receives a contribution
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `recipient` - Wallet receiving the collected amount when the vault is closed
+    pub fn set_recipient(
+        ctx: Context<SetRecipient>,
+        payment_id: u32,
+        recipient: Pubkey,
+    ) -> Result<()> {
+        instructions::set_recipient::handler(ctx, payment_id, recipient)
+    }
+
+    /// Issuer shares the collected amount between several recipients, until the vault receives a contribution
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `payout_recipients` - Recipients and their part in basis points, adding up to 10 000. Leave empty to pay
+    ///   the single vault recipient with `close_vault`
+    pub fn set_payout_recipients(
+        ctx: Context<SetPayoutRecipients>,
+        payment_id: u32,
+        payout_recipients: Vec<PayoutRecipient>,
+    ) -> Result<()> {
+        instructions::set_payout_recipients::handler(ctx, payment_id, payout_recipients)
+    }
+
+    /// Issuer closes the vault and shares the collected amount between the payout recipients, passed as
+    /// writable remaining accounts in the order of the vault list
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn distribute<'info>(
+        ctx: Context<'_, '_, 'info, 'info, Distribute<'info>>,
+        payment_id: u32,
+    ) -> Result<()> {
+        instructions::distribute::handler(ctx, payment_id)
+    }
+
+    /// Issuer defines the partial releases of the vault, until the vault receives a contribution
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `milestones` - Amounts released to the recipient and the number of contributor approvals each one needs,
+    ///   adding up to at most the total amount
+    pub fn set_milestones(
+        ctx: Context<SetMilestones>,
+        payment_id: u32,
+        milestones: Vec<MilestoneArgs>,
+    ) -> Result<()> {
+        instructions::set_milestones::handler(ctx, payment_id, milestones)
+    }
+
+    /// Contributor approves the release of a milestone
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `index` - Index of the milestone in the vault list
+    pub fn approve_milestone(
+        ctx: Context<ApproveMilestone>,
+        payment_id: u32,
+        index: u8,
+    ) -> Result<()> {
+        instructions::approve_milestone::handler(ctx, payment_id, index)
+    }
+
+    /// Issuer releases an approved milestone amount of a funded vault to the recipient, the rest of the
+    /// vault stays refundable
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `index` - Index of the milestone in the vault list
+    pub fn release_milestone(
+        ctx: Context<ReleaseMilestone>,
+        payment_id: u32,
+        index: u8,
+    ) -> Result<()> {
+        instructions::release_milestone::handler(ctx, payment_id, index)
+    }
+
+    /// Issuer locks the funds of the funded vault until a quorum of contributors approves the release, until the
+    /// vault receives a contribution
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `release_quorum` - Number of contributors, or part of the collected amount in basis points, that must
+    ///   approve. `None` lets the issuer close the vault alone
+    /// - `release_deadline` - Unix timestamp after which contributors can reclaim their funds if the release
+    ///   isn't approved, required along with a quorum so the funds can't stay locked forever
+    pub fn set_release_quorum(
+        ctx: Context<SetReleaseQuorum>,
+        payment_id: u32,
+        release_quorum: Option<ReleaseQuorum>,
+        release_deadline: Option<i64>,
+    ) -> Result<()> {
+        instructions::set_release_quorum::handler(ctx, payment_id, release_quorum, release_deadline)
+    }
+
+    /// Contributor approves the release of the funds of a funded vault
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn approve_release(ctx: Context<ApproveRelease>, payment_id: u32) -> Result<()> {
+        instructions::approve_release::handler(ctx, payment_id)
+    }
+
+    /// Issuer appoints a neutral arbiter resolving disputes, until the vault receives a contribution
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `arbiter` - Arbiter wallet, can't be the issuer. `None` disables disputes
+    /// - `dispute_window` - Seconds the arbiter has to resolve a dispute before contributors can claim their
+    ///   refund, `DEFAULT_DISPUTE_WINDOW` when `None`
+    pub fn set_arbiter(
+        ctx: Context<SetArbiter>,
+        payment_id: u32,
+        arbiter: Option<Pubkey>,
+        dispute_window: Option<i64>,
+    ) -> Result<()> {
+        instructions::set_arbiter::handler(ctx, payment_id, arbiter, dispute_window)
+    }
+
+    /// Contributor disputes the payment, freezing every payout and refund until the arbiter resolves it or
+    /// its dispute window is over. A vault can only be disputed until the arbiter ruled once
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn open_dispute(ctx: Context<OpenDispute>, payment_id: u32) -> Result<()> {
+        instructions::open_dispute::handler(ctx, payment_id)
+    }
+
+    /// Arbiter resolves the dispute by releasing the funds or by cancelling the payment
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `resolution` - `Release` lets the issuer close the vault without contributor approvals, `Refund` cancels
+    ///   the payment so contributors claim their refund pro-rata of the escrowed amount
+    pub fn resolve_dispute(
+        ctx: Context<ResolveDispute>,
+        payment_id: u32,
+        resolution: DisputeResolution,
+    ) -> Result<()> {
+        instructions::resolve_dispute::handler(ctx, payment_id, resolution)
+    }
+
+    /// Issuer drains the lamports stranded in the vault authority of a closed vault
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn recover_vault_authority(
+        ctx: Context<RecoverVaultAuthority>,
+        payment_id: u32,
+    ) -> Result<()> {
+        instructions::recover_vault_authority::handler(ctx, payment_id)
+    }
+
+    /// Issuer drains the tokens stranded in the vault token account of a closed vault and closes it
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn recover_vault_authority_token(
+        ctx: Context<RecoverVaultAuthorityToken>,
+        payment_id: u32,
+    ) -> Result<()> {
+        instructions::recover_vault_authority_token::handler(ctx, payment_id)
+    }
+
+    /// Participant moves his participant vault from the legacy `[participant, payment_id]` seeds to the seeds
+    /// derived from the vault address. The escrowed contribution moves to the vault authority and counts toward
+    /// the vault, the legacy account rent is sent back to him. A vault that can't take the contribution, closed,
+    /// still in the legacy layout or no longer open, refunds it to the participant instead
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn migrate_participant_vault(
+        ctx: Context<MigrateParticipantVault>,
+        payment_id: u32,
+    ) -> Result<()> {
+        instructions::migrate_participant_vault::handler(ctx, payment_id)
+    }
+
+    /// Issuer refunds many participants in one transaction and closes their participant vaults, passed as
+    /// writable (participant, participant vault) pairs in the remaining accounts
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn refund_participants_batch<'info>(
+        ctx: Context<'_, '_, 'info, 'info, RefundParticipantsBatch<'info>>,
+        payment_id: u32,
+    ) -> Result<()> {
+        instructions::refund_participants_batch::handler(ctx, payment_id)
+    }
+
+    /// Create a vault with the next payment id of the issuer profile, created with the first vault. Allocated ids
+    /// start at `FIRST_PROFILE_PAYMENT_ID`, the payment id is read from the `VaultCreated` event or from the issuer
+    /// profile before the call
+    ///
+    /// ### Parameters
+    /// - `total_amount` - Total amount to collect, in base units of the vault asset (lamports for SOL)
+    /// - `expires_at` - Optional unix timestamp after which contributions are rejected and participants can claim a refund
+    /// - `recipient` - Optional wallet receiving the collected amount when the vault is closed, the issuer if omitted
+    /// - `split_mode` - How `total_amount` is split between the debtors, see `initialize_vault`
+    /// - `shares` - Debtors and their share value for the split mode. Leave empty for an open vault
+    /// - `metadata` - Title, description, currency hint, invoice reference and optional URI of the payment
+    pub fn create_vault(
+        ctx: Context<CreateVault>,
+        total_amount: u64,
+        expires_at: Option<i64>,
+        recipient: Option<Pubkey>,
+        split_mode: SplitMode,
+        shares: Vec<InvoiceShareArgs>,
+        metadata: PaymentMetadataArgs,
+    ) -> Result<()> {
+        instructions::create_vault::handler(
+            ctx,
+            total_amount,
+            expires_at,
+            recipient,
+            split_mode,
+            shares,
+            metadata,
+        )
+    }
+
+    /// Issuer creates or replaces the metadata describing the payment, until the vault receives a contribution
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    /// - `metadata` - Title, description, currency hint, invoice reference and optional URI of the payment
+    pub fn set_metadata(
+        ctx: Context<SetMetadata>,
+        payment_id: u32,
+        metadata: PaymentMetadataArgs,
+    ) -> Result<()> {
+        instructions::set_metadata::handler(ctx, payment_id, metadata)
+    }
+
+    /// Participant gets the rent of his participant vault back once the payment vault is closed
+    ///
+    /// ### Parameters
+    /// - `payment_id` - Unique payment ID to find vault PDA
+    pub fn close_participant_vault(
+        ctx: Context<CloseParticipantVault>,
+        payment_id: u32,
+    ) -> Result<()> {
+        instructions::close_participant_vault::handler(ctx, payment_id)
     }
 }
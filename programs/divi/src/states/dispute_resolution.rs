@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq)]
+pub enum DisputeResolution {
+    // The funds can be released to the recipient, contributor approvals aren't required anymore
+    Release,
+
+    // The payment is cancelled, contributors claim their refund pro-rata of the escrowed amount
+    Refund,
+}
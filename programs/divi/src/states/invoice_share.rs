@@ -0,0 +1,38 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum SplitMode {
+    // Each share value is the exact amount owed
+    #[default]
+    Exact,
+
+    // The total is split equally, share values are ignored
+    Equal,
+
+    // Each share value is a percentage of the total in basis points, adding up to 10 000
+    BasisPoints,
+
+    // Each share value is an arbitrary weight relative to the sum of the weights
+    Weights,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct InvoiceShareArgs {
+    // Debtor registered by the issuer
+    pub participant: Pubkey,
+
+    // Exact amount, basis points or weight depending on the split mode
+    pub value: u64,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct InvoiceShare {
+    // Debtor registered by the issuer
+    pub participant: Pubkey,
+
+    // Exact amount owed by the debtor, in base units of the vault asset
+    pub amount: u64,
+
+    // Flag to indicate if the debtor paid his share
+    pub is_paid: bool,
+}
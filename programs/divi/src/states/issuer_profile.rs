@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::FIRST_PROFILE_PAYMENT_ID, errors::DiviError};
+
+#[account]
+#[derive(Default, InitSpace)]
+pub struct IssuerProfile {
+    // The issuer owning the profile
+    pub issuer: Pubkey,
+
+    // Payment id assigned to the next vault created through `create_vault`, zero until the first one
+    pub next_payment_id: u32,
+
+    // Number of vaults created by the issuer, through `initialize_vault` or `create_vault`
+    pub vault_count: u64,
+
+    // The bump
+    pub bump: u8,
+}
+
+impl IssuerProfile {
+    /**
+     * Payment id of the next vault, profile allocated ids start at `FIRST_PROFILE_PAYMENT_ID` so
+     * they never collide with the ids picked in `initialize_vault`
+     */
+    pub fn payment_id(&self) -> u32 {
+        self.next_payment_id.max(FIRST_PROFILE_PAYMENT_ID)
+    }
+
+    /**
+     * Count a new vault of the issuer and return its nonce, unique among the vaults of the issuer
+     * even when a payment id is reused once its vault is closed
+     */
+    pub fn next_vault_nonce(&mut self) -> Result<u64> {
+        self.vault_count = self
+            .vault_count
+            .checked_add(1)
+            .ok_or(DiviError::ArithmeticOverflow)?;
+
+        Ok(self.vault_count)
+    }
+}
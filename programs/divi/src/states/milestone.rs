@@ -0,0 +1,25 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct MilestoneArgs {
+    // Amount released to the recipient, in base units of the vault asset
+    pub amount: u64,
+
+    // Number of contributors that must approve the release, 0 to let the issuer release it alone
+    pub approvals_required: u8,
+}
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct Milestone {
+    // Amount released to the recipient, in base units of the vault asset
+    pub amount: u64,
+
+    // Number of contributors that must approve the release
+    pub approvals_required: u8,
+
+    // Number of contributors that approved the release
+    pub approvals: u8,
+
+    // Flag to indicate if the amount was released
+    pub is_released: bool,
+}
@@ -1,5 +1,21 @@
+mod dispute_resolution;
+mod invoice_share;
+mod issuer_profile;
+mod milestone;
 mod participant_vault;
+mod payment_metadata;
 mod payment_vault;
+mod payout_recipient;
+mod release_quorum;
+mod vault_status;
 
+pub use dispute_resolution::*;
+pub use invoice_share::*;
+pub use issuer_profile::*;
+pub use milestone::*;
 pub use participant_vault::*;
+pub use payment_metadata::*;
 pub use payment_vault::*;
+pub use payout_recipient::*;
+pub use release_quorum::*;
+pub use vault_status::*;
@@ -1,4 +1,6 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{constants::ANCHOR_DISCRIMINATOR, errors::DiviError};
 
 #[account]
 #[derive(Default, InitSpace)]
@@ -9,12 +11,96 @@ pub struct ParticipantVault {
     // The payment id
     pub payment_id: u32,
 
+    // Nonce of the payment vault the contribution was made to
+    pub vault_nonce: u64,
+
     // The current participant
     pub participant: Pubkey,
 
-    // The amount paid
+    // The amount contributed to the vault, held by the vault authority
     pub amount: u64,
 
+    // Milestones approved by the participant, one bit per milestone index
+    pub approved_milestones: u8,
+
+    // Flag to indicate if the participant approved the release of the funds
+    pub has_approved_release: bool,
+
     // The bump
     pub bump: u8,
 }
+
+/**
+* Participant vault derived from the legacy `[participant, payment_id]` seeds, written before the
+* approvals were added so it no longer deserializes as a `ParticipantVault`
+*/
+#[derive(AnchorDeserialize)]
+pub struct LegacyParticipantVault {
+    pub issuer: Pubkey,
+    pub payment_id: u32,
+    pub participant: Pubkey,
+    pub amount: u64,
+    pub bump: u8,
+}
+
+impl LegacyParticipantVault {
+    pub const LEN: usize = ANCHOR_DISCRIMINATOR + 32 + 4 + 32 + 8 + 1;
+
+    /**
+     * Decode the account data, checking its size and the `ParticipantVault` discriminator
+     */
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() == Self::LEN
+                && data[..ANCHOR_DISCRIMINATOR] == ParticipantVault::DISCRIMINATOR,
+            DiviError::InvalidParticipantVault
+        );
+
+        Self::deserialize(&mut &data[ANCHOR_DISCRIMINATOR..])
+            .map_err(|_| DiviError::InvalidParticipantVault.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_account(issuer: Pubkey, participant: Pubkey) -> Vec<u8> {
+        let mut data = ParticipantVault::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(issuer.as_ref());
+        data.extend_from_slice(&42u32.to_le_bytes());
+        data.extend_from_slice(participant.as_ref());
+        data.extend_from_slice(&1_500u64.to_le_bytes());
+        data.push(254);
+        data
+    }
+
+    #[test]
+    fn legacy_participant_vault_is_decoded() {
+        let issuer = Pubkey::new_unique();
+        let participant = Pubkey::new_unique();
+        let data = legacy_account(issuer, participant);
+
+        let legacy = LegacyParticipantVault::try_from_bytes(&data).unwrap();
+
+        assert_eq!(data.len(), LegacyParticipantVault::LEN);
+        assert_eq!(legacy.issuer, issuer);
+        assert_eq!(legacy.payment_id, 42);
+        assert_eq!(legacy.participant, participant);
+        assert_eq!(legacy.amount, 1_500);
+        assert_eq!(legacy.bump, 254);
+    }
+
+    #[test]
+    fn legacy_participant_vault_rejects_other_layouts() {
+        let mut data = legacy_account(Pubkey::new_unique(), Pubkey::new_unique());
+
+        // A migrated participant vault is longer than the legacy layout
+        let mut current = data.clone();
+        current.extend_from_slice(&[0, 0]);
+        assert!(LegacyParticipantVault::try_from_bytes(&current).is_err());
+
+        data[0] ^= 1;
+        assert!(LegacyParticipantVault::try_from_bytes(&data).is_err());
+    }
+}
@@ -0,0 +1,82 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{
+        MAX_CURRENCY_LENGTH, MAX_DESCRIPTION_LENGTH, MAX_INVOICE_REFERENCE_LENGTH,
+        MAX_TITLE_LENGTH, MAX_URI_LENGTH,
+    },
+    errors::DiviError,
+};
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone)]
+pub struct PaymentMetadataArgs {
+    // Short title displayed to the contributors
+    pub title: String,
+
+    // Longer description of the payment
+    pub description: String,
+
+    // Currency the amounts are displayed in by clients, e.g. "EUR", amounts stay in base units
+    pub currency: String,
+
+    // Reference of the invoice in the issuer accounting system
+    pub invoice_reference: String,
+
+    // Optional link to an off-chain document, e.g. the invoice PDF
+    pub uri: Option<String>,
+}
+
+#[account]
+#[derive(Default, InitSpace)]
+pub struct PaymentMetadata {
+    // The payment vault described by this account
+    pub vault: Pubkey,
+
+    #[max_len(MAX_TITLE_LENGTH)]
+    pub title: String,
+
+    #[max_len(MAX_DESCRIPTION_LENGTH)]
+    pub description: String,
+
+    #[max_len(MAX_CURRENCY_LENGTH)]
+    pub currency: String,
+
+    #[max_len(MAX_INVOICE_REFERENCE_LENGTH)]
+    pub invoice_reference: String,
+
+    #[max_len(MAX_URI_LENGTH)]
+    pub uri: Option<String>,
+
+    // The bump
+    pub bump: u8,
+}
+
+impl PaymentMetadata {
+    /**
+     * Replace the metadata after checking each field against its length limit
+     */
+    pub fn update(&mut self, args: PaymentMetadataArgs) -> Result<()> {
+        require!(
+            args.title.len() <= MAX_TITLE_LENGTH,
+            DiviError::TitleTooLong
+        );
+        require!(
+            args.description.len() <= MAX_DESCRIPTION_LENGTH,
+            DiviError::DescriptionTooLong
+        );
+        require!(
+            args.currency.len() <= MAX_CURRENCY_LENGTH
+                && args.invoice_reference.len() <= MAX_INVOICE_REFERENCE_LENGTH
+                && args.uri.as_ref().map_or(0, |uri| uri.len()) <= MAX_URI_LENGTH,
+            DiviError::StringTooLong
+        );
+
+        self.title = args.title;
+        self.description = args.description;
+        self.currency = args.currency;
+        self.invoice_reference = args.invoice_reference;
+        self.uri = args.uri;
+
+        Ok(())
+    }
+}
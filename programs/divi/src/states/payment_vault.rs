@@ -1,4 +1,13 @@
-use anchor_lang::prelude::*;
+use anchor_lang::{prelude::*, Discriminator};
+
+use crate::{
+    constants::{
+        ANCHOR_DISCRIMINATOR, BASIS_POINTS, MAX_INVOICE_SHARES, MAX_MILESTONES,
+        MAX_PAYOUT_RECIPIENTS,
+    },
+    errors::DiviError,
+    states::{InvoiceShare, Milestone, PayoutRecipient, ReleaseQuorum, SplitMode, VaultStatus},
+};
 
 #[account]
 #[derive(Default, InitSpace)]
@@ -9,11 +18,17 @@ pub struct PaymentVault {
     // Amount to pay
     pub total_amount: u64,
 
-    // Flag to indicate if the total amount was paid
-    pub is_finalized: bool,
+    // Amount currently escrowed or paid toward the total, refunds and withdrawals excluded
+    pub collected_amount: u64,
 
-    // Flag to indicate if the payment is cancelled
-    pub is_cancelled: bool,
+    // Part of the collected amount already released to the recipient through milestones
+    pub released_amount: u64,
+
+    // Number of participant vaults open on this vault, each one closed by a refund or withdrawal
+    pub participant_count: u32,
+
+    // Lifecycle of the vault, only changed through `transition`
+    pub status: VaultStatus,
 
     // Bump from the PDA generation
     pub bump: u8,
@@ -21,6 +36,293 @@ pub struct PaymentVault {
     // Unique ID for the PDA
     pub payment_id: u32,
 
+    // Nonce of the vault among the issuer vaults, participant vaults of a closed vault that had
+    // the same address carry another one
+    pub nonce: u64,
+
     // Vault authority
     pub authority: Pubkey,
+
+    // SPL Token or Token-2022 mint, `None` for a native SOL vault
+    pub mint: Option<Pubkey>,
+
+    // Decimals of the vault asset, amounts are expressed in base units (lamports for SOL)
+    pub decimals: u8,
+
+    // Unix timestamp after which contributions are rejected and participants can claim a refund
+    pub expires_at: Option<i64>,
+
+    // Wallet receiving the collected amount when the vault is closed, the issuer by default
+    pub recipient: Pubkey,
+
+    // Flag set by the first contribution, the recipient and the other payment terms can't be
+    // changed anymore
+    pub has_contributions: bool,
+
+    // Wallets sharing the collected amount in basis points, paid by `distribute` instead of the
+    // single recipient when not empty
+    #[max_len(MAX_PAYOUT_RECIPIENTS)]
+    pub payout_recipients: Vec<PayoutRecipient>,
+
+    // Partial releases defined by the issuer, approved by the contributors
+    #[max_len(MAX_MILESTONES)]
+    pub milestones: Vec<Milestone>,
+
+    // Contributor approvals required to release the funds once the vault is funded, `None` to let
+    // the issuer close the vault alone
+    pub release_quorum: Option<ReleaseQuorum>,
+
+    // Unix timestamp after which contributors can reclaim their funds if the release isn't approved
+    pub release_deadline: Option<i64>,
+
+    // Number of contributors that approved the release
+    pub release_approvals: u32,
+
+    // Amount contributed by the contributors that approved the release
+    pub release_approved_amount: u64,
+
+    // Neutral party resolving the disputes opened by contributors
+    pub arbiter: Option<Pubkey>,
+
+    // Seconds the arbiter has to resolve a dispute
+    pub dispute_window: i64,
+
+    // Time after which contributors can claim their refund if the arbiter didn't resolve the
+    // open dispute
+    pub dispute_deadline: Option<i64>,
+
+    // Flag to indicate if the arbiter ruled on a dispute, the ruling is final
+    pub is_dispute_resolved: bool,
+
+    // How the invoice shares were derived from the total amount
+    pub split_mode: SplitMode,
+
+    // Debtors and their exact shares in invoice mode, empty for an open vault
+    #[max_len(MAX_INVOICE_SHARES)]
+    pub shares: Vec<InvoiceShare>,
+}
+
+impl PaymentVault {
+    /**
+     * Remove a contribution from the collected amount and return the part still escrowed that can
+     * be sent back. Once milestones are released, each contribution is refunded pro-rata of the
+     * amount left in the vault
+     */
+    pub fn release_contribution(&mut self, participant: Pubkey, amount: u64) -> Result<u64> {
+        let refund_amount = if self.released_amount == 0 || amount == 0 {
+            amount
+        } else {
+            (amount as u128)
+                .checked_mul(self.escrowed_amount()? as u128)
+                .and_then(|product| product.checked_div(self.collected_amount as u128))
+                .and_then(|refund_amount| u64::try_from(refund_amount).ok())
+                .ok_or(DiviError::ArithmeticOverflow)?
+        };
+
+        self.collected_amount = self
+            .collected_amount
+            .checked_sub(amount)
+            .ok_or(DiviError::ArithmeticOverflow)?;
+        self.released_amount = self
+            .released_amount
+            .checked_sub(amount - refund_amount)
+            .ok_or(DiviError::ArithmeticOverflow)?;
+
+        // The debtor owes his whole share again
+        if let Some(share) = self.share_mut(&participant) {
+            share.is_paid = false;
+        }
+
+        Ok(refund_amount)
+    }
+
+    /**
+     * Remove a contributor whose participant vault is closed from the participant count and from
+     * the approvals of the milestones not released yet
+     */
+    pub fn remove_participant(&mut self, approved_milestones: u8) -> Result<()> {
+        self.participant_count = self
+            .participant_count
+            .checked_sub(1)
+            .ok_or(DiviError::ArithmeticOverflow)?;
+
+        for (index, milestone) in self.milestones.iter_mut().enumerate() {
+            if !milestone.is_released && approved_milestones & (1 << index) != 0 {
+                milestone.approvals = milestone.approvals.saturating_sub(1);
+            }
+        }
+
+        Ok(())
+    }
+
+    pub fn escrowed_amount(&self) -> Result<u64> {
+        self.collected_amount
+            .checked_sub(self.released_amount)
+            .ok_or(error!(DiviError::ArithmeticOverflow))
+    }
+
+    pub fn remaining_amount(&self) -> u64 {
+        self.total_amount.saturating_sub(self.collected_amount)
+    }
+
+    pub fn is_expired(&self, now: i64) -> bool {
+        self.expires_at.is_some_and(|expires_at| now >= expires_at)
+    }
+
+    pub fn is_invoice(&self) -> bool {
+        !self.shares.is_empty()
+    }
+
+    pub fn share_mut(&mut self, participant: &Pubkey) -> Option<&mut InvoiceShare> {
+        self.shares
+            .iter_mut()
+            .find(|share| share.participant == *participant)
+    }
+
+    pub fn is_release_approved(&self) -> bool {
+        match self.release_quorum {
+            None => true,
+            Some(ReleaseQuorum::Contributors { approvals }) => self.release_approvals >= approvals,
+            Some(ReleaseQuorum::Amount { basis_points }) => {
+                self.release_approved_amount as u128 * BASIS_POINTS as u128
+                    >= self.collected_amount as u128 * basis_points as u128
+            }
+        }
+    }
+
+    pub fn is_dispute_expired(&self, now: i64) -> bool {
+        self.dispute_deadline
+            .is_some_and(|dispute_deadline| now >= dispute_deadline)
+    }
+
+    pub fn is_release_expired(&self, now: i64) -> bool {
+        !self.is_release_approved()
+            && self
+                .release_deadline
+                .is_some_and(|release_deadline| now >= release_deadline)
+    }
+
+    /**
+     * Move the vault to a new status, rejecting the transitions not allowed from the current one
+     */
+    pub fn transition(&mut self, status: VaultStatus) -> Result<()> {
+        require!(self.status.can_transition_to(status), self.status.error());
+
+        self.status = status;
+
+        Ok(())
+    }
+
+    /**
+     * Reject the instruction if the vault is not in one of the given statuses
+     */
+    pub fn require_status(&self, statuses: &[VaultStatus]) -> Result<()> {
+        require!(statuses.contains(&self.status), self.status.error());
+
+        Ok(())
+    }
+
+    /**
+     * Check contributors can reclaim their funds, expiring the vault once its deadline is over
+     */
+    pub fn open_refunds(&mut self, now: i64) -> Result<()> {
+        match self.status {
+            VaultStatus::Cancelled | VaultStatus::Expired => Ok(()),
+            VaultStatus::Open if self.is_expired(now) => self.transition(VaultStatus::Expired),
+            VaultStatus::Funded if self.is_release_expired(now) => {
+                self.transition(VaultStatus::Expired)
+            }
+            VaultStatus::Disputed if self.is_dispute_expired(now) => {
+                self.transition(VaultStatus::Cancelled)
+            }
+            VaultStatus::Open | VaultStatus::Funded => err!(DiviError::RefundNotClaimable),
+            VaultStatus::Released | VaultStatus::Disputed => Err(self.status.error().into()),
+        }
+    }
+}
+
+/**
+* Payment vault written before the status and the contribution accounting were added, it keeps
+* its contributions on the vault authority without participant vaults so no instruction can load
+* it as a `PaymentVault` anymore
+*/
+#[derive(AnchorDeserialize)]
+pub struct LegacyPaymentVault {
+    pub issuer: Pubkey,
+    pub total_amount: u64,
+    pub is_finalized: bool,
+    pub is_cancelled: bool,
+    pub bump: u8,
+    pub payment_id: u32,
+    pub authority: Pubkey,
+}
+
+impl LegacyPaymentVault {
+    pub const LEN: usize = ANCHOR_DISCRIMINATOR + 32 + 8 + 1 + 1 + 1 + 4 + 32;
+
+    /**
+     * Decode the account data, checking its size and the `PaymentVault` discriminator
+     */
+    pub fn try_from_bytes(data: &[u8]) -> Result<Self> {
+        require!(
+            data.len() == Self::LEN && data[..ANCHOR_DISCRIMINATOR] == PaymentVault::DISCRIMINATOR,
+            DiviError::InvalidPaymentVault
+        );
+
+        Self::deserialize(&mut &data[ANCHOR_DISCRIMINATOR..])
+            .map_err(|_| DiviError::InvalidPaymentVault.into())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn legacy_account(issuer: Pubkey) -> Vec<u8> {
+        let mut data = PaymentVault::DISCRIMINATOR.to_vec();
+        data.extend_from_slice(issuer.as_ref());
+        data.extend_from_slice(&10_000u64.to_le_bytes());
+        data.extend_from_slice(&[0, 1, 253]);
+        data.extend_from_slice(&42u32.to_le_bytes());
+        data.extend_from_slice(Pubkey::default().as_ref());
+        data
+    }
+
+    #[test]
+    fn expired_dispute_opens_the_refunds() {
+        let mut vault = PaymentVault {
+            status: VaultStatus::Disputed,
+            dispute_deadline: Some(100),
+            ..Default::default()
+        };
+
+        assert!(vault.open_refunds(99).is_err());
+        assert!(vault.open_refunds(100).is_ok());
+        assert!(vault.status == VaultStatus::Cancelled);
+    }
+
+    #[test]
+    fn legacy_payment_vault_is_decoded() {
+        let issuer = Pubkey::new_unique();
+        let data = legacy_account(issuer);
+
+        let legacy = LegacyPaymentVault::try_from_bytes(&data).unwrap();
+
+        assert_eq!(data.len(), LegacyPaymentVault::LEN);
+        assert_eq!(legacy.issuer, issuer);
+        assert_eq!(legacy.total_amount, 10_000);
+        assert!(!legacy.is_finalized);
+        assert!(legacy.is_cancelled);
+        assert_eq!(legacy.bump, 253);
+        assert_eq!(legacy.payment_id, 42);
+    }
+
+    #[test]
+    fn legacy_payment_vault_rejects_current_layout() {
+        let mut data = Vec::new();
+        PaymentVault::default().try_serialize(&mut data).unwrap();
+
+        assert!(data.len() > LegacyPaymentVault::LEN);
+        assert!(LegacyPaymentVault::try_from_bytes(&data).is_err());
+    }
 }
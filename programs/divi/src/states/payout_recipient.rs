@@ -0,0 +1,10 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Default, InitSpace)]
+pub struct PayoutRecipient {
+    // Wallet receiving part of the collected amount
+    pub recipient: Pubkey,
+
+    // Part of the collected amount in basis points
+    pub basis_points: u16,
+}
@@ -0,0 +1,11 @@
+use anchor_lang::prelude::*;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, PartialEq, Eq, InitSpace)]
+pub enum ReleaseQuorum {
+    // Number of contributors that must approve, each contributor weighs the same
+    Contributors { approvals: u32 },
+
+    // Part of the collected amount in basis points that must approve, each contributor weighs
+    // his contribution
+    Amount { basis_points: u16 },
+}
@@ -0,0 +1,92 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::DiviError;
+
+#[derive(AnchorSerialize, AnchorDeserialize, Clone, Copy, Default, PartialEq, Eq, InitSpace)]
+pub enum VaultStatus {
+    // The vault accepts contributions until the total amount is collected
+    #[default]
+    Open,
+
+    // The total amount is collected, the funds wait for the release
+    Funded,
+
+    // The collected amount was paid out to the recipients
+    Released,
+
+    // The payment was cancelled by the issuer or the arbiter, contributors claim their refund
+    Cancelled,
+
+    // The vault wasn't funded or its release approved in time, contributors claim their refund
+    Expired,
+
+    // A contributor disputed the payment, payouts are frozen until the arbiter resolves it
+    Disputed,
+}
+
+impl VaultStatus {
+    /**
+     * Every status change of a vault goes through this table, `Released`, `Cancelled` and
+     * `Expired` are final
+     */
+    pub fn can_transition_to(self, status: VaultStatus) -> bool {
+        matches!(
+            (self, status),
+            (
+                Self::Open,
+                Self::Funded | Self::Cancelled | Self::Expired | Self::Disputed
+            ) | (
+                Self::Funded,
+                Self::Released | Self::Expired | Self::Disputed
+            ) | (Self::Disputed, Self::Open | Self::Funded | Self::Cancelled)
+        )
+    }
+
+    /**
+     * Error explaining why a vault in this status rejects an instruction
+     */
+    pub fn error(self) -> DiviError {
+        match self {
+            Self::Open => DiviError::VaultIsNotFinalized,
+            Self::Funded | Self::Released => DiviError::VaultIsAlreadyFinalized,
+            Self::Cancelled => DiviError::VaultIsCancelled,
+            Self::Expired => DiviError::VaultIsExpired,
+            Self::Disputed => DiviError::VaultIsDisputed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VaultStatus::{self, *};
+
+    const STATUSES: [VaultStatus; 6] = [Open, Funded, Released, Cancelled, Expired, Disputed];
+
+    const ALLOWED: [(VaultStatus, VaultStatus); 10] = [
+        (Open, Funded),
+        (Open, Cancelled),
+        (Open, Expired),
+        (Open, Disputed),
+        (Funded, Released),
+        (Funded, Expired),
+        (Funded, Disputed),
+        (Disputed, Open),
+        (Disputed, Funded),
+        (Disputed, Cancelled),
+    ];
+
+    #[test]
+    fn can_transition_to_matches_the_table() {
+        for (i, from) in STATUSES.into_iter().enumerate() {
+            for (j, to) in STATUSES.into_iter().enumerate() {
+                assert_eq!(
+                    from.can_transition_to(to),
+                    ALLOWED.contains(&(from, to)),
+                    "transition from STATUSES[{}] to STATUSES[{}]",
+                    i,
+                    j
+                );
+            }
+        }
+    }
+}
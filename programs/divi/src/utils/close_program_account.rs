@@ -0,0 +1,23 @@
+use anchor_lang::{prelude::*, system_program};
+
+use crate::errors::DiviError;
+
+/**
+* Close an account owned by the program that isn't loaded as an `Account`, sending its rent to
+* `destination` and giving it back to the system program
+*/
+pub fn close_program_account<'info>(
+    account: &AccountInfo<'info>,
+    destination: &AccountInfo<'info>,
+) -> Result<()> {
+    **destination.try_borrow_mut_lamports()? = destination
+        .lamports()
+        .checked_add(account.lamports())
+        .ok_or(DiviError::ArithmeticOverflow)?;
+    **account.try_borrow_mut_lamports()? = 0;
+
+    account.assign(&system_program::ID);
+    account.realloc(0, false)?;
+
+    Ok(())
+}
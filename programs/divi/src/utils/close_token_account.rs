@@ -0,0 +1,61 @@
+use anchor_lang::prelude::*;
+use anchor_spl::{
+    token_2022::spl_token_2022::{
+        extension::{
+            transfer_fee::TransferFeeAmount, BaseStateWithExtensions, StateWithExtensions,
+        },
+        state::Account,
+    },
+    token_interface::{
+        close_account, harvest_withheld_tokens_to_mint, CloseAccount, HarvestWithheldTokensToMint,
+        Mint, TokenAccount, TokenInterface,
+    },
+};
+
+/**
+* Close an empty token account owned by a program PDA and send its rent to `destination`
+*/
+pub fn close_token_account<'info>(
+    account: &InterfaceAccount<'info, TokenAccount>,
+    destination: &AccountInfo<'info>,
+    authority: &AccountInfo<'info>,
+    mint: &InterfaceAccount<'info, Mint>,
+    token_program: &Interface<'info, TokenInterface>,
+    signer_seeds: &[&[&[u8]]],
+) -> Result<()> {
+    // Token-2022 refuses to close an account holding withheld transfer fees,
+    // so move them to the mint first (permissionless)
+    if withheld_transfer_fees(account)? > 0 {
+        harvest_withheld_tokens_to_mint(
+            CpiContext::new(
+                token_program.to_account_info(),
+                HarvestWithheldTokensToMint {
+                    token_program_id: token_program.to_account_info(),
+                    mint: mint.to_account_info(),
+                },
+            ),
+            vec![account.to_account_info()],
+        )?;
+    }
+
+    close_account(CpiContext::new_with_signer(
+        token_program.to_account_info(),
+        CloseAccount {
+            account: account.to_account_info(),
+            destination: destination.clone(),
+            authority: authority.clone(),
+        },
+        signer_seeds,
+    ))
+}
+
+fn withheld_transfer_fees(account: &InterfaceAccount<TokenAccount>) -> Result<u64> {
+    let account_info = account.to_account_info();
+    let data = account_info.try_borrow_data()?;
+    let state = StateWithExtensions::<Account>::unpack(&data)?;
+
+    Ok(state
+        .get_extension::<TransferFeeAmount>()
+        .map(|extension| u64::from(extension.withheld_amount))
+        .unwrap_or(0))
+}
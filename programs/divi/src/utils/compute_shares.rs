@@ -0,0 +1,79 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::{BASIS_POINTS, MAX_INVOICE_SHARES},
+    errors::DiviError,
+    states::{InvoiceShare, InvoiceShareArgs, SplitMode},
+    utils::split_amount,
+};
+
+/**
+* Derive the amount owed by each debtor from the total amount and the split mode, the remaining
+* base units after rounding go to the first debtors in registration order
+*/
+pub fn compute_shares(
+    total_amount: u64,
+    split_mode: SplitMode,
+    shares: &[InvoiceShareArgs],
+) -> Result<Vec<InvoiceShare>> {
+    // Check if the debtors are unique and within the limit
+    require!(
+        !shares.is_empty() && shares.len() <= MAX_INVOICE_SHARES,
+        DiviError::InvalidInvoiceShares
+    );
+
+    for (index, share) in shares.iter().enumerate() {
+        require!(
+            !shares[..index]
+                .iter()
+                .any(|other| other.participant == share.participant),
+            DiviError::InvalidInvoiceShares
+        );
+    }
+
+    let values: Vec<u64> = match split_mode {
+        SplitMode::Equal => vec![1; shares.len()],
+        _ => shares.iter().map(|share| share.value).collect(),
+    };
+
+    // Check if every debtor owes something
+    require!(
+        values.iter().all(|value| *value > 0),
+        DiviError::InvalidInvoiceShares
+    );
+
+    let values_total = values
+        .iter()
+        .try_fold(0u64, |sum, value| sum.checked_add(*value))
+        .ok_or(DiviError::ArithmeticOverflow)?;
+
+    match split_mode {
+        SplitMode::Exact => require!(
+            values_total == total_amount,
+            DiviError::InvalidInvoiceShares
+        ),
+        SplitMode::BasisPoints => require!(
+            values_total == BASIS_POINTS,
+            DiviError::InvalidInvoiceShares
+        ),
+        SplitMode::Equal | SplitMode::Weights => {}
+    }
+
+    let amounts = split_amount(total_amount, &values)?;
+
+    // Check if every debtor owes at least one base unit after the split
+    require!(
+        amounts.iter().all(|amount| *amount > 0),
+        DiviError::ShareCalculationError
+    );
+
+    Ok(shares
+        .iter()
+        .zip(amounts)
+        .map(|(share, amount)| InvoiceShare {
+            participant: share.participant,
+            amount,
+            is_paid: false,
+        })
+        .collect())
+}
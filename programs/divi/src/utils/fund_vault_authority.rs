@@ -0,0 +1,42 @@
+use anchor_lang::prelude::*;
+
+/**
+* Top the vault authority of a SOL vault up to the rent exempt minimum, paid by the issuer, so
+* contributions and refunds of any size leave it rent exempt. The rent goes back to the issuer
+* when the vault is released or closed
+*/
+pub fn fund_vault_authority<'info>(
+    issuer: &AccountInfo<'info>,
+    vault_authority: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+) -> Result<()> {
+    let amount = Rent::get()?
+        .minimum_balance(0)
+        .saturating_sub(vault_authority.lamports());
+
+    if amount == 0 {
+        return Ok(());
+    }
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new(
+            system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: issuer.clone(),
+                to: vault_authority.clone(),
+            },
+        ),
+        amount,
+    )
+}
+
+/**
+* Part of the vault authority balance that refunds the rent funded by the issuer, taken from the
+* lamports exceeding the escrowed contributions so a vault created before the authority was
+* funded still pays its contributions out in full
+*/
+pub fn vault_authority_rent(balance: u64, escrowed_amount: u64) -> Result<u64> {
+    Ok(Rent::get()?
+        .minimum_balance(0)
+        .min(balance.saturating_sub(escrowed_amount)))
+}
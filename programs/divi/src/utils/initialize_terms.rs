@@ -0,0 +1,48 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    errors::DiviError,
+    states::{InvoiceShareArgs, PaymentVault, SplitMode, VaultStatus},
+    utils::compute_shares,
+};
+
+/**
+* Set the payment terms of a new vault once its issuer is set, after checking the deadline is in
+* the future and deriving the amount owed by each debtor in invoice mode
+*/
+pub fn initialize_terms(
+    vault: &mut PaymentVault,
+    total_amount: u64,
+    expires_at: Option<i64>,
+    recipient: Option<Pubkey>,
+    split_mode: SplitMode,
+    shares: Vec<InvoiceShareArgs>,
+) -> Result<()> {
+    // Check if the deadline is in the future
+    if let Some(expires_at) = expires_at {
+        require!(
+            expires_at > Clock::get()?.unix_timestamp,
+            DiviError::InvalidExpiration
+        );
+    }
+
+    // Derive the amount owed by each debtor in invoice mode
+    let shares = if shares.is_empty() {
+        Vec::new()
+    } else {
+        compute_shares(total_amount, split_mode, &shares)?
+    };
+
+    vault.total_amount = total_amount;
+    vault.collected_amount = 0;
+    vault.participant_count = 0;
+    vault.status = VaultStatus::Open;
+    vault.expires_at = expires_at;
+    vault.recipient = recipient.unwrap_or(vault.issuer);
+    vault.has_contributions = false;
+    vault.arbiter = None;
+    vault.split_mode = split_mode;
+    vault.shares = shares;
+
+    Ok(())
+}
@@ -1,3 +1,23 @@
-pub mod is_valid_participant_vault;
+pub mod close_program_account;
+pub mod close_token_account;
+pub mod compute_shares;
+pub mod create_program_account;
+pub mod fund_vault_authority;
+pub mod initialize_terms;
+pub mod record_contribution;
+pub mod split_amount;
+pub mod transfer_from_vault;
+pub mod transfer_tokens;
+pub mod withdraw_contribution;
 
-pub use is_valid_participant_vault::*;
+pub use close_program_account::*;
+pub use close_token_account::*;
+pub use compute_shares::*;
+pub use create_program_account::*;
+pub use fund_vault_authority::*;
+pub use initialize_terms::*;
+pub use record_contribution::*;
+pub use split_amount::*;
+pub use transfer_from_vault::*;
+pub use transfer_tokens::*;
+pub use withdraw_contribution::*;
@@ -0,0 +1,131 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::EVENT_VERSION,
+    errors::DiviError,
+    events::{ParticipantJoined, ParticipantPaid, VaultCompleted},
+    states::{ParticipantVault, PaymentVault, VaultStatus},
+};
+
+/**
+* Add a contribution credited to the vault authority to the participant ledger and the vault
+* collected amount, after checking it against the vault total and remaining amounts, and the
+* participant share in invoice mode. The vault is finalized once fully funded
+*/
+pub fn record_contribution(
+    vault: &mut PaymentVault,
+    participant_vault: &mut ParticipantVault,
+    participant: Pubkey,
+    amount: u64,
+) -> Result<()> {
+    // Check if the vault still accepts contributions
+    vault.require_status(&[VaultStatus::Open])?;
+    require!(
+        !vault.is_expired(Clock::get()?.unix_timestamp),
+        DiviError::VaultIsExpired
+    );
+
+    // Check if the contribution is not empty
+    require!(amount > 0, DiviError::InvalidAmount);
+
+    // Check if the amount is not greater than the vault total amount
+    require!(
+        amount <= vault.total_amount,
+        DiviError::AmountIsGreaterThanVaultTotalAmount
+    );
+
+    // Check if the amount is not greater than the remaining amount needed
+    require!(
+        amount <= vault.total_amount.saturating_sub(vault.collected_amount),
+        DiviError::AmountIsGreaterThanRemainingVaultAmount
+    );
+
+    // Check if the debtor pays exactly his share, once, in invoice mode
+    if vault.is_invoice() {
+        let share = vault
+            .share_mut(&participant)
+            .ok_or(DiviError::ParticipantNotInInvoice)?;
+
+        require!(!share.is_paid, DiviError::ParticipantAlreadyPaid);
+        require!(amount == share.amount, DiviError::AmountDoesNotMatchShare);
+
+        share.is_paid = true;
+    }
+
+    // Lock the recipient and the payment terms once the vault received a contribution
+    vault.has_contributions = true;
+
+    // Count the participant once, when his participant vault is created. A participant vault left
+    // by a closed vault that had the same address was paid out already, it starts over
+    let is_new_participant = participant_vault.vault_nonce != vault.nonce;
+    if is_new_participant {
+        participant_vault.vault_nonce = vault.nonce;
+        participant_vault.amount = 0;
+        participant_vault.approved_milestones = 0;
+        participant_vault.has_approved_release = false;
+
+        vault.participant_count = vault
+            .participant_count
+            .checked_add(1)
+            .ok_or(DiviError::ArithmeticOverflow)?;
+    }
+
+    participant_vault.participant = participant;
+    participant_vault.payment_id = vault.payment_id;
+    participant_vault.issuer = vault.issuer;
+    participant_vault.amount = participant_vault
+        .amount
+        .checked_add(amount)
+        .ok_or(DiviError::ArithmeticOverflow)?;
+    vault.collected_amount = vault
+        .collected_amount
+        .checked_add(amount)
+        .ok_or(DiviError::ArithmeticOverflow)?;
+
+    let timestamp = Clock::get()?.unix_timestamp;
+
+    if is_new_participant {
+        emit!(ParticipantJoined {
+            version: EVENT_VERSION,
+            issuer: vault.issuer,
+            payment_id: vault.payment_id,
+            bump: vault.bump,
+            participant,
+            amount,
+            collected_amount: vault.collected_amount,
+            remaining_amount: vault.remaining_amount(),
+            participant_count: vault.participant_count,
+            timestamp,
+        });
+    }
+
+    emit!(ParticipantPaid {
+        version: EVENT_VERSION,
+        issuer: vault.issuer,
+        payer: participant,
+        payment_id: vault.payment_id,
+        bump: vault.bump,
+        amount,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp,
+    });
+
+    // Check if vault is fully funded after this contribution
+    if vault.collected_amount >= vault.total_amount {
+        vault.transition(VaultStatus::Funded)?;
+
+        emit!(VaultCompleted {
+            version: EVENT_VERSION,
+            issuer: vault.issuer,
+            payment_id: vault.payment_id,
+            bump: vault.bump,
+            amount,
+            collected_amount: vault.collected_amount,
+            remaining_amount: vault.remaining_amount(),
+            timestamp,
+        });
+    }
+
+    Ok(())
+}
@@ -0,0 +1,44 @@
+use anchor_lang::prelude::*;
+
+use crate::errors::DiviError;
+
+/**
+* Split an amount proportionally to the given values. Each part is `amount * value / sum of values`
+* rounded down, the remaining base units are assigned one by one to the first parts
+*/
+pub fn split_amount(amount: u64, values: &[u64]) -> Result<Vec<u64>> {
+    let values_total = values
+        .iter()
+        .try_fold(0u64, |sum, value| sum.checked_add(*value))
+        .ok_or(DiviError::ArithmeticOverflow)?;
+
+    require!(values_total > 0, DiviError::ShareCalculationError);
+
+    let mut parts = values
+        .iter()
+        .map(|value| {
+            (amount as u128)
+                .checked_mul(*value as u128)
+                .and_then(|product| product.checked_div(values_total as u128))
+                .and_then(|part| u64::try_from(part).ok())
+                .ok_or(error!(DiviError::ShareCalculationError))
+        })
+        .collect::<Result<Vec<u64>>>()?;
+
+    // Assign the remainder left by the rounding, it's lower than the number of parts
+    let distributed = parts
+        .iter()
+        .try_fold(0u64, |sum, part| sum.checked_add(*part))
+        .ok_or(DiviError::ShareCalculationError)?;
+    let remainder = amount
+        .checked_sub(distributed)
+        .ok_or(DiviError::ShareCalculationError)?;
+
+    for part in parts.iter_mut().take(remainder as usize) {
+        *part = part
+            .checked_add(1)
+            .ok_or(DiviError::ShareCalculationError)?;
+    }
+
+    Ok(parts)
+}
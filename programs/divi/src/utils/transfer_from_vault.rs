@@ -0,0 +1,34 @@
+use anchor_lang::prelude::*;
+
+use crate::{constants::VAULT_AUTHORITY, states::PaymentVault};
+
+/**
+* Transfer lamports held by the vault authority PDA, signing with its seeds
+*/
+pub fn transfer_from_vault<'info>(
+    vault: &PaymentVault,
+    vault_authority: &AccountInfo<'info>,
+    vault_authority_bump: u8,
+    to: &AccountInfo<'info>,
+    system_program: &AccountInfo<'info>,
+    amount: u64,
+) -> Result<()> {
+    let authority_seeds = &[
+        VAULT_AUTHORITY.as_bytes(),
+        vault.issuer.as_ref(),
+        &vault.payment_id.to_le_bytes(),
+        &[vault_authority_bump],
+    ];
+
+    anchor_lang::system_program::transfer(
+        CpiContext::new_with_signer(
+            system_program.clone(),
+            anchor_lang::system_program::Transfer {
+                from: vault_authority.clone(),
+                to: to.clone(),
+            },
+            &[&authority_seeds[..]],
+        ),
+        amount,
+    )
+}
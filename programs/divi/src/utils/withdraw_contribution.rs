@@ -0,0 +1,53 @@
+use anchor_lang::prelude::*;
+
+use crate::{
+    constants::EVENT_VERSION,
+    errors::DiviError,
+    events::ParticipantWithdrew,
+    states::{ParticipantVault, PaymentVault},
+};
+
+/**
+* Remove a withdrawn amount from the participant ledger and the vault collected amount, and
+* return the amount to send back, lower than the withdrawn amount once milestones are released
+*/
+pub fn withdraw_contribution(
+    vault: &mut PaymentVault,
+    participant_vault: &mut ParticipantVault,
+    amount: u64,
+) -> Result<u64> {
+    // Check if the amount is within the participant contribution
+    require!(
+        amount > 0 && amount <= participant_vault.amount,
+        DiviError::InvalidWithdrawalAmount
+    );
+
+    // Check if the whole share is withdrawn in invoice mode, the debtor must pay it again exactly
+    require!(
+        !vault.is_invoice() || amount == participant_vault.amount,
+        DiviError::InvalidWithdrawalAmount
+    );
+
+    participant_vault.amount -= amount;
+    let refund_amount = vault.release_contribution(participant_vault.participant, amount)?;
+
+    // The participant leaves the vault once everything is withdrawn, his vault is closed
+    if participant_vault.amount == 0 {
+        vault.remove_participant(participant_vault.approved_milestones)?;
+    }
+
+    emit!(ParticipantWithdrew {
+        version: EVENT_VERSION,
+        issuer: vault.issuer,
+        payment_id: vault.payment_id,
+        bump: vault.bump,
+        participant: participant_vault.participant,
+        amount,
+        participant_amount: participant_vault.amount,
+        collected_amount: vault.collected_amount,
+        remaining_amount: vault.remaining_amount(),
+        timestamp: Clock::get()?.unix_timestamp,
+    });
+
+    Ok(refund_amount)
+}
@@ -0,0 +1,139 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  authorityRent,
+  expectError,
+  initializeVault,
+  participate,
+  sol,
+} from "./helpers";
+import { getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi batch refund", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customers = Array.from({ length: 8 }, () =>
+    anchor.web3.Keypair.generate()
+  );
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId);
+
+    for (const customer of customers) {
+      await airdrop(provider, customer.publicKey, 10);
+      await participate(program, issuer.publicKey, paymentId, customer, sol(1));
+    }
+
+    await program.methods
+      .cancelPayment(paymentId)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+  });
+
+  it("Issuer can't pass an incomplete pair", async () => {
+    await expectError(
+      refundBatch([
+        { pubkey: customers[0].publicKey, isWritable: true, isSigner: false },
+      ]),
+      "InvalidRefundBatch"
+    );
+  });
+
+  it("Issuer can't pair a participant with another participant vault", async () => {
+    await expectError(
+      refundBatch([
+        { pubkey: customers[0].publicKey, isWritable: true, isSigner: false },
+        {
+          pubkey: participantVault(customers[1]),
+          isWritable: true,
+          isSigner: false,
+        },
+      ]),
+      "InvalidParticipantVault"
+    );
+  });
+
+  it("Issuer refund every participant in one transaction", async () => {
+    const signature = await refundBatch(
+      customers.flatMap((customer) => [
+        { pubkey: customer.publicKey, isWritable: true, isSigner: false },
+        {
+          pubkey: participantVault(customer),
+          isWritable: true,
+          isSigner: false,
+        },
+      ])
+    );
+
+    const tx = await provider.connection.getTransaction(signature, {
+      commitment: "confirmed",
+      maxSupportedTransactionVersion: 0,
+    });
+    const events = [
+      ...new anchor.EventParser(program.programId, program.coder).parseLogs(
+        tx.meta.logMessages
+      ),
+    ].filter((event) => event.name === "participantRefunded");
+
+    console.log(
+      `Compute units: ${tx.meta.computeUnitsConsumed} for ${
+        customers.length
+      } participants, ${Math.ceil(
+        tx.meta.computeUnitsConsumed / customers.length
+      )} per participant`
+    );
+
+    expect(events.length).equal(customers.length);
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      await authorityRent(provider)
+    );
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.participantCount).equal(0);
+    expect(vaultData.collectedAmount.toNumber()).equal(0);
+  });
+
+  function participantVault(customer: anchor.web3.Keypair) {
+    return getParticipantPdas(
+      issuer.publicKey,
+      customer.publicKey,
+      paymentId,
+      program.programId
+    ).vault;
+  }
+
+  async function refundBatch(remainingAccounts: anchor.web3.AccountMeta[]) {
+    return await program.methods
+      .refundParticipantsBatch(paymentId)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(remainingAccounts)
+      .signers([issuer])
+      .rpc({ commitment: "confirmed" });
+  }
+});
@@ -0,0 +1,94 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  authorityRent,
+  cancelPayment,
+  claimRefund,
+  expectError,
+  initializeVault,
+  participate,
+  sol,
+} from "./helpers";
+import { getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi self-refund after cancellation", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const amount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId, { totalAmount: amount });
+
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(4));
+  });
+
+  it("Customer A can't claim a refund while the vault is open", async () => {
+    await expectError(
+      claimRefund(program, issuer.publicKey, paymentId, customerA),
+      "RefundNotClaimable"
+    );
+  });
+
+  it("Issuer cancel the payment and leave", async () => {
+    await cancelPayment(program, issuer, paymentId);
+
+    const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultAccount.status).to.deep.equal({ cancelled: {} });
+  });
+
+  it("Customer B can't participate to a cancelled vault", async () => {
+    await expectError(
+      participate(program, issuer.publicKey, paymentId, customerB, sol(2)),
+      "VaultIsCancelled"
+    );
+  });
+
+  it("Customer A claim his refund without the issuer", async () => {
+    const participantVault = getParticipantPdas(
+      issuer.publicKey,
+      customerA.publicKey,
+      paymentId,
+      program.programId
+    ).vault;
+    const rent = await provider.connection.getBalance(participantVault);
+    const balanceBefore = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+
+    await claimRefund(program, issuer.publicKey, paymentId, customerA);
+
+    const balanceAfter = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+
+    // Contribution plus rent, minus the transaction fee
+    expect(balanceAfter - balanceBefore).to.be.closeTo(
+      4 * anchor.web3.LAMPORTS_PER_SOL + rent,
+      10000
+    );
+    expect(await provider.connection.getAccountInfo(participantVault)).to.be
+      .null;
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      await authorityRent(provider)
+    );
+  });
+});
@@ -2,3 +2,6 @@ export const VAULT = "divi-vault";
 export const VAULT_AUTHORITY = "divi-vault-authority";
 export const PARTICIPANT_VAULT = "participant_vault";
 export const PARTICIPANT_VAULT_AUTHORITY = "participant_vault_authority";
+export const ISSUER_PROFILE = "issuer_profile";
+export const PAYMENT_METADATA = "payment_metadata";
+export const FIRST_PROFILE_PAYMENT_ID = 2 ** 31;
@@ -0,0 +1,272 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  authorityRent,
+  chainTime,
+  claimRefund,
+  closeVault,
+  expectError,
+  initializeVault,
+  openDispute,
+  participate,
+  sol,
+} from "./helpers";
+import { getVaultPdas } from "./pdas";
+
+describe("Divi arbiter disputes", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const issuer = anchor.web3.Keypair.generate();
+  const arbiter = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, arbiter.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+  });
+
+  describe("Dispute resolved with a release", () => {
+    const paymentId = Math.floor(Math.random() * 100000);
+
+    before(async () => {
+      await createVault(paymentId);
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(6)
+      );
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerB,
+        sol(4)
+      );
+    });
+
+    it("Issuer can't change the arbiter once the vault is funded", async () => {
+      await expectError(setArbiter(paymentId, null), "InvalidArbiter");
+    });
+
+    it("Customer A dispute freezes the payout", async () => {
+      await openDispute(program, issuer.publicKey, paymentId, customerA);
+
+      await expectError(
+        openDispute(program, issuer.publicKey, paymentId, customerB),
+        "VaultIsDisputed"
+      );
+      await expectError(
+        closeVault(program, issuer, paymentId),
+        "VaultIsDisputed"
+      );
+    });
+
+    it("Only the arbiter can resolve the dispute", async () => {
+      await expectError(
+        resolveDispute(paymentId, customerB, { release: {} }),
+        "InvalidArbiter"
+      );
+    });
+
+    it("Customers can't dispute again once the arbiter ruled", async () => {
+      await resolveDispute(paymentId, arbiter, { release: {} });
+
+      await expectError(
+        openDispute(program, issuer.publicKey, paymentId, customerB),
+        "DisputeAlreadyResolved"
+      );
+    });
+
+    it("Issuer close the vault once the arbiter released the funds", async () => {
+      await closeVault(program, issuer, paymentId);
+
+      const { vault } = getVaultPdas(
+        issuer.publicKey,
+        paymentId,
+        program.programId
+      );
+
+      expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+    });
+  });
+
+  describe("Dispute resolved with a refund", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 100000;
+
+    before(async () => {
+      await createVault(paymentId);
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(6)
+      );
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerB,
+        sol(4)
+      );
+      await openDispute(program, issuer.publicKey, paymentId, customerB);
+    });
+
+    it("Customers can't reclaim their funds while disputed", async () => {
+      await expectError(
+        claimRefund(program, issuer.publicKey, paymentId, customerA),
+        "VaultIsDisputed"
+      );
+    });
+
+    it("Customers reclaim their funds once the arbiter refunded", async () => {
+      await resolveDispute(paymentId, arbiter, { refund: {} });
+
+      await expectError(
+        resolveDispute(paymentId, arbiter, { release: {} }),
+        "VaultIsNotDisputed"
+      );
+      await expectError(
+        closeVault(program, issuer, paymentId),
+        "VaultIsCancelled"
+      );
+
+      await claimRefund(program, issuer.publicKey, paymentId, customerA);
+      await claimRefund(program, issuer.publicKey, paymentId, customerB);
+
+      const { vaultAuthority } = getVaultPdas(
+        issuer.publicKey,
+        paymentId,
+        program.programId
+      );
+
+      expect(await provider.connection.getBalance(vaultAuthority)).equal(
+        await authorityRent(provider)
+      );
+    });
+  });
+
+  describe("Dispute not resolved before its deadline", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 300000;
+    const { vault } = getVaultPdas(
+      issuer.publicKey,
+      paymentId,
+      program.programId
+    );
+
+    before(async () => {
+      await createVault(paymentId, true, new anchor.BN(3));
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(10)
+      );
+      await openDispute(program, issuer.publicKey, paymentId, customerA);
+    });
+
+    it("Customer A can't reclaim his funds before the deadline", async () => {
+      await expectError(
+        claimRefund(program, issuer.publicKey, paymentId, customerA),
+        "VaultIsDisputed"
+      );
+    });
+
+    it("Customer A reclaim his funds once the deadline is over", async () => {
+      const { disputeDeadline } = await program.account.paymentVault.fetch(
+        vault
+      );
+
+      while ((await chainTime(provider)) <= disputeDeadline.toNumber()) {
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+      }
+
+      await claimRefund(program, issuer.publicKey, paymentId, customerA);
+
+      const vaultData = await program.account.paymentVault.fetch(vault);
+
+      expect(vaultData.status).to.deep.equal({ cancelled: {} });
+      await expectError(
+        resolveDispute(paymentId, arbiter, { release: {} }),
+        "VaultIsNotDisputed"
+      );
+    });
+  });
+
+  describe("Vault without arbiter", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 200000;
+
+    before(async () => {
+      await createVault(paymentId, false);
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(10)
+      );
+    });
+
+    it("Customer A can't dispute the payment", async () => {
+      await expectError(
+        openDispute(program, issuer.publicKey, paymentId, customerA),
+        "NoArbiter"
+      );
+    });
+  });
+
+  async function createVault(
+    paymentId: number,
+    withArbiter = true,
+    disputeWindow: anchor.BN | null = null
+  ) {
+    await initializeVault(program, issuer, paymentId);
+
+    if (withArbiter) {
+      await setArbiter(paymentId, arbiter.publicKey, disputeWindow);
+    }
+  }
+
+  async function setArbiter(
+    paymentId: number,
+    arbiterKey: anchor.web3.PublicKey | null,
+    disputeWindow: anchor.BN | null = null
+  ) {
+    await program.methods
+      .setArbiter(paymentId, arbiterKey, disputeWindow)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault: getVaultPdas(issuer.publicKey, paymentId, program.programId)
+          .vault,
+      })
+      .signers([issuer])
+      .rpc();
+  }
+
+  async function resolveDispute(
+    paymentId: number,
+    signer: anchor.web3.Keypair,
+    resolution: Parameters<typeof program.methods.resolveDispute>[1]
+  ) {
+    await program.methods
+      .resolveDispute(paymentId, resolution)
+      .accountsStrict({
+        arbiter: signer.publicKey,
+        vault: getVaultPdas(issuer.publicKey, paymentId, program.programId)
+          .vault,
+      })
+      .signers([signer])
+      .rpc();
+  }
+});
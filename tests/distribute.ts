@@ -0,0 +1,177 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert, expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  getIssuerProfilePda,
+  getMetadataPda,
+  getParticipantPdas,
+  getVaultPdas,
+} from "./pdas";
+
+describe("Divi multi-recipient payout", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  // 10 SOL and 1 lamport, so the split leaves a remainder
+  const amount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL + 1);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const host = anchor.web3.Keypair.generate();
+  const cleaning = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+
+    await program.methods
+      .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+        vault,
+        vaultAuthority,
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+  });
+
+  it("Issuer can't set allocations that don't add up to 100%", async () => {
+    try {
+      await setPayoutRecipients(7000, 2000);
+
+      assert.fail("Allocations not adding up to 10 000 should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "InvalidPayoutRecipients"
+      );
+    }
+  });
+
+  it("Issuer split the payout between the host and the cleaning service", async () => {
+    await setPayoutRecipients(7000, 3000);
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.payoutRecipients.length).equal(2);
+  });
+
+  it("Customer A pay the whole amount", async () => {
+    await program.methods
+      .participate(paymentId, amount)
+      .accountsStrict({
+        participant: customerA.publicKey,
+        vault,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customerA.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customerA])
+      .rpc();
+  });
+
+  it("Issuer can't close the vault to a single recipient", async () => {
+    try {
+      await program.methods
+        .closeVault(paymentId)
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          recipient: issuer.publicKey,
+          vault,
+          vaultAuthority,
+          metadata: getMetadataPda(vault, program.programId),
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+
+      assert.fail("Close with payout recipients should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "PayoutRecipientsConfigured"
+      );
+    }
+  });
+
+  it("Issuer can't distribute to recipients in the wrong order", async () => {
+    try {
+      await distribute([cleaning.publicKey, host.publicKey]);
+
+      assert.fail("Distribution to unexpected recipients should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "InvalidRecipient"
+      );
+    }
+  });
+
+  it("Issuer distribute 70% to the host and 30% to the cleaning service", async () => {
+    await distribute([host.publicKey, cleaning.publicKey]);
+
+    // The remaining lamport goes to the first recipient
+    expect(await provider.connection.getBalance(host.publicKey)).equal(
+      7 * anchor.web3.LAMPORTS_PER_SOL + 1
+    );
+    expect(await provider.connection.getBalance(cleaning.publicKey)).equal(
+      3 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(0);
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+  });
+
+  async function setPayoutRecipients(hostBps: number, cleaningBps: number) {
+    await program.methods
+      .setPayoutRecipients(paymentId, [
+        { recipient: host.publicKey, basisPoints: hostBps },
+        { recipient: cleaning.publicKey, basisPoints: cleaningBps },
+      ])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+      })
+      .signers([issuer])
+      .rpc();
+  }
+
+  async function distribute(recipients: anchor.web3.PublicKey[]) {
+    await program.methods
+      .distribute(paymentId)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        metadata: getMetadataPda(vault, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .remainingAccounts(
+        recipients.map((pubkey) => ({
+          pubkey,
+          isWritable: true,
+          isSigner: false,
+        }))
+      )
+      .signers([issuer])
+      .rpc();
+  }
+});
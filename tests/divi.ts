@@ -4,6 +4,12 @@ import { assert, expect } from "chai";
 import { Divi } from "../target/types/divi";
 import { airdrop } from "./airdrop";
 import { VAULT, VAULT_AUTHORITY } from "./constants";
+import { authorityRent } from "./helpers";
+import {
+  getIssuerProfilePda,
+  getMetadataPda,
+  getParticipantPdas,
+} from "./pdas";
 
 describe("divi", () => {
   const provider = anchor.AnchorProvider.env();
@@ -47,11 +53,13 @@ describe("divi", () => {
 
   it("Create the vault", async () => {
     const tx = await program.methods
-      .initializeVault(paymentId, amount)
+      .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
       .accountsStrict({
         issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
         vault,
         vaultAuthority,
+        mint: null,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([issuer])
@@ -63,19 +71,26 @@ describe("divi", () => {
 
     expect(vaultAccount.issuer.toBase58()).equal(issuer.publicKey.toBase58());
     expect(vaultAccount.totalAmount.toNumber()).equal(amount.toNumber());
-    expect(vaultAccount.isFinalized).to.be.false;
+    expect(vaultAccount.status).to.deep.equal({ open: {} });
     expect(vaultAccount.paymentId).equal(paymentId);
+    expect(vaultAccount.decimals).equal(9);
   });
 
   it("Customer A try to deposit an amount which is greater than the vault total amount", async () => {
     try {
       const tx = await program.methods
-        .pay(paymentId, new anchor.BN(15))
+        .pay(paymentId, new anchor.BN(15 * anchor.web3.LAMPORTS_PER_SOL))
         .accountsStrict({
           payer: customerA.publicKey,
           issuer: issuer.publicKey,
           vault,
           vaultAuthority,
+          participantVault: getParticipantPdas(
+            issuer.publicKey,
+            customerA.publicKey,
+            paymentId,
+            program.programId
+          ).vault,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
         .signers([customerA])
@@ -98,12 +113,18 @@ describe("divi", () => {
     actualAmount += 4;
 
     const tx = await program.methods
-      .pay(paymentId, new anchor.BN(4))
+      .pay(paymentId, new anchor.BN(4 * anchor.web3.LAMPORTS_PER_SOL))
       .accountsStrict({
         payer: customerA.publicKey,
         issuer: issuer.publicKey,
         vault,
         vaultAuthority,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customerA.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([customerA])
@@ -114,19 +135,28 @@ describe("divi", () => {
       vaultAuthority
     );
 
-    expect(balance).equal(actualAmount * anchor.web3.LAMPORTS_PER_SOL);
+    expect(balance).equal(
+      actualAmount * anchor.web3.LAMPORTS_PER_SOL +
+        (await authorityRent(provider))
+    );
   });
 
   it("Issuer share the vault, customer B pay", async () => {
     actualAmount += 4;
 
     const tx = await program.methods
-      .pay(paymentId, new anchor.BN(4))
+      .pay(paymentId, new anchor.BN(4 * anchor.web3.LAMPORTS_PER_SOL))
       .accountsStrict({
         payer: customerB.publicKey,
         issuer: issuer.publicKey,
         vault,
         vaultAuthority,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customerB.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([customerB])
@@ -137,7 +167,10 @@ describe("divi", () => {
       vaultAuthority
     );
 
-    expect(balance).equal(actualAmount * anchor.web3.LAMPORTS_PER_SOL);
+    expect(balance).equal(
+      actualAmount * anchor.web3.LAMPORTS_PER_SOL +
+        (await authorityRent(provider))
+    );
   });
 
   it("Issuer try to close the vault, but not finalized for the moment", async () => {
@@ -146,8 +179,10 @@ describe("divi", () => {
         .closeVault(paymentId)
         .accountsStrict({
           issuer: issuer.publicKey,
+          recipient: issuer.publicKey,
           vault,
           vaultAuthority,
+          metadata: getMetadataPda(vault, program.programId),
           systemProgram: anchor.web3.SystemProgram.programId,
         })
         .signers([issuer])
@@ -166,12 +201,18 @@ describe("divi", () => {
     actualAmount += 2;
 
     const tx = await program.methods
-      .pay(paymentId, new anchor.BN(2))
+      .pay(paymentId, new anchor.BN(2 * anchor.web3.LAMPORTS_PER_SOL))
       .accountsStrict({
         payer: customerC.publicKey,
         issuer: issuer.publicKey,
         vault,
         vaultAuthority,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customerC.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([customerC])
@@ -182,18 +223,27 @@ describe("divi", () => {
       vaultAuthority
     );
 
-    expect(balance).equal(actualAmount * anchor.web3.LAMPORTS_PER_SOL);
+    expect(balance).equal(
+      actualAmount * anchor.web3.LAMPORTS_PER_SOL +
+        (await authorityRent(provider))
+    );
   });
 
   it("Issuer share the vault, customer C pay again, but the program doesn't works", async () => {
     try {
       const tx = await program.methods
-        .pay(paymentId, new anchor.BN(2))
+        .pay(paymentId, new anchor.BN(2 * anchor.web3.LAMPORTS_PER_SOL))
         .accountsStrict({
           payer: customerC.publicKey,
           issuer: issuer.publicKey,
           vault,
           vaultAuthority,
+          participantVault: getParticipantPdas(
+            issuer.publicKey,
+            customerC.publicKey,
+            paymentId,
+            program.programId
+          ).vault,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
         .signers([customerC])
@@ -211,7 +261,7 @@ describe("divi", () => {
   it("Vault is finalized", async () => {
     const vaultAccount = await program.account.paymentVault.fetch(vault);
 
-    expect(vaultAccount.isFinalized).to.be.true;
+    expect(vaultAccount.status).to.deep.equal({ funded: {} });
   });
 
   it("Issuer close the vault", async () => {
@@ -219,8 +269,10 @@ describe("divi", () => {
       .closeVault(paymentId)
       .accountsStrict({
         issuer: issuer.publicKey,
+        recipient: issuer.publicKey,
         vault,
         vaultAuthority,
+        metadata: getMetadataPda(vault, program.programId),
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([issuer])
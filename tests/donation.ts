@@ -0,0 +1,117 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  getIssuerProfilePda,
+  getMetadataPda,
+  getParticipantPdas,
+  getVaultPdas,
+} from "./pdas";
+
+describe("Divi stray donations to the vault authority", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const amount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const donor = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, donor.publicKey, 100);
+
+    await program.methods
+      .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+        vault,
+        vaultAuthority,
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+  });
+
+  it("Donor send 3 SOL directly to the vault authority", async () => {
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: donor.publicKey,
+          toPubkey: vaultAuthority,
+          lamports: 3 * anchor.web3.LAMPORTS_PER_SOL,
+        })
+      ),
+      [donor]
+    );
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.collectedAmount.toNumber()).equal(0);
+    expect(vaultData.status).to.deep.equal({ open: {} });
+  });
+
+  it("Customer A still pay the whole 10 SOL and finalize the vault", async () => {
+    await program.methods
+      .participate(paymentId, amount)
+      .accountsStrict({
+        participant: customerA.publicKey,
+        vault,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customerA.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customerA])
+      .rpc();
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.collectedAmount.toNumber()).equal(amount.toNumber());
+    expect(vaultData.status).to.deep.equal({ funded: {} });
+  });
+
+  it("Issuer close the vault and receive the collected amount and the donation", async () => {
+    const balanceBefore = await provider.connection.getBalance(
+      issuer.publicKey
+    );
+
+    await program.methods
+      .closeVault(paymentId)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        recipient: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        metadata: getMetadataPda(vault, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const balanceAfter = await provider.connection.getBalance(
+      issuer.publicKey
+    );
+
+    expect(balanceAfter - balanceBefore).to.be.greaterThan(
+      13 * anchor.web3.LAMPORTS_PER_SOL - 10000
+    );
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(0);
+  });
+});
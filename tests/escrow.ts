@@ -0,0 +1,172 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  approveRelease,
+  authorityRent,
+  chainTime,
+  claimRefund,
+  closeVault,
+  expectError,
+  initializeVault,
+  participate,
+  sol,
+} from "./helpers";
+import { getVaultPdas } from "./pdas";
+
+describe("Divi contributor approved release", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+  });
+
+  describe("Release approved by 60% of the collected amount", () => {
+    const paymentId = Math.floor(Math.random() * 100000);
+
+    before(async () => {
+      await createVault(
+        paymentId,
+        { amount: { basisPoints: 6000 } },
+        new anchor.BN((await chainTime(provider)) + 3600)
+      );
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(7)
+      );
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerB,
+        sol(3)
+      );
+    });
+
+    it("Issuer can't close the funded vault without approvals", async () => {
+      await expectError(
+        closeVault(program, issuer, paymentId),
+        "ReleaseNotApproved"
+      );
+    });
+
+    it("Customer B approval alone isn't enough", async () => {
+      await approveRelease(program, issuer.publicKey, paymentId, customerB);
+
+      await expectError(
+        closeVault(program, issuer, paymentId),
+        "ReleaseNotApproved"
+      );
+      await expectError(
+        approveRelease(program, issuer.publicKey, paymentId, customerB),
+        "ReleaseAlreadyApproved"
+      );
+    });
+
+    it("Customer A approve and the issuer close the vault", async () => {
+      await approveRelease(program, issuer.publicKey, paymentId, customerA);
+      await closeVault(program, issuer, paymentId);
+
+      const { vault } = getVaultPdas(
+        issuer.publicKey,
+        paymentId,
+        program.programId
+      );
+
+      expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+    });
+  });
+
+  describe("Release quorum without a deadline", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 200000;
+
+    it("Issuer can't lock the funds without a release deadline", async () => {
+      await expectError(
+        createVault(paymentId, { contributors: { approvals: 1 } }, null),
+        "InvalidReleaseQuorum"
+      );
+    });
+  });
+
+  describe("Release not approved before the deadline", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 100000;
+    let releaseDeadline: number;
+
+    before(async () => {
+      releaseDeadline = (await chainTime(provider)) + 5;
+
+      await createVault(
+        paymentId,
+        { contributors: { approvals: 2 } },
+        new anchor.BN(releaseDeadline)
+      );
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(10)
+      );
+    });
+
+    it("Customer A can't reclaim his funds before the deadline", async () => {
+      await expectError(
+        claimRefund(program, issuer.publicKey, paymentId, customerA),
+        "RefundNotClaimable"
+      );
+    });
+
+    it("Customer A reclaim his funds once the deadline is over", async () => {
+      while ((await chainTime(provider)) <= releaseDeadline) {
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+      }
+
+      await expectError(
+        approveRelease(program, issuer.publicKey, paymentId, customerA),
+        "VaultIsExpired"
+      );
+      await claimRefund(program, issuer.publicKey, paymentId, customerA);
+
+      const { vaultAuthority } = getVaultPdas(
+        issuer.publicKey,
+        paymentId,
+        program.programId
+      );
+
+      expect(await provider.connection.getBalance(vaultAuthority)).equal(
+        await authorityRent(provider)
+      );
+    });
+  });
+
+  async function createVault(
+    paymentId: number,
+    releaseQuorum: Parameters<typeof program.methods.setReleaseQuorum>[1],
+    releaseDeadline: anchor.BN | null
+  ) {
+    await initializeVault(program, issuer, paymentId);
+
+    await program.methods
+      .setReleaseQuorum(paymentId, releaseQuorum, releaseDeadline)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault: getVaultPdas(issuer.publicKey, paymentId, program.programId)
+          .vault,
+      })
+      .signers([issuer])
+      .rpc();
+  }
+});
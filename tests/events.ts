@@ -0,0 +1,119 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  closeVault,
+  getEvents,
+  initializeVault,
+  participate,
+  sol,
+} from "./helpers";
+
+describe("Divi events", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customer1 = anchor.web3.Keypair.generate();
+  const customer2 = anchor.web3.Keypair.generate();
+  const eventVersion = 1;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customer1.publicKey, 10);
+    await airdrop(provider, customer2.publicKey, 10);
+  });
+
+  it("Vault creation carries the total amount", async () => {
+    const signature = await initializeVault(program, issuer, paymentId, {
+      totalAmount: sol(3),
+    });
+
+    const [event] = await getEvents(program, signature, "vaultCreated");
+
+    expect(event.version).equal(eventVersion);
+    expect(event.amount.toNumber()).equal(sol(3).toNumber());
+    expect(event.collectedAmount.toNumber()).equal(0);
+    expect(event.remainingAmount.toNumber()).equal(sol(3).toNumber());
+    expect(event.timestamp.toNumber()).greaterThan(0);
+  });
+
+  it("First contribution emits a participant joined event", async () => {
+    const signature = await participate(
+      program,
+      issuer.publicKey,
+      paymentId,
+      customer1,
+      sol(1)
+    );
+
+    const [joined] = await getEvents(program, signature, "participantJoined");
+    const [paid] = await getEvents(program, signature, "participantPaid");
+
+    expect(joined.version).equal(eventVersion);
+    expect(joined.participant.equals(customer1.publicKey)).to.be.true;
+    expect(joined.amount.toNumber()).equal(sol(1).toNumber());
+    expect(joined.participantCount).equal(1);
+
+    expect(paid.version).equal(eventVersion);
+    expect(paid.amount.toNumber()).equal(sol(1).toNumber());
+    expect(paid.collectedAmount.toNumber()).equal(sol(1).toNumber());
+    expect(paid.remainingAmount.toNumber()).equal(sol(2).toNumber());
+    expect(paid.timestamp.toNumber()).equal(joined.timestamp.toNumber());
+  });
+
+  it("Next contributions of the participant don't join again", async () => {
+    const signature = await participate(
+      program,
+      issuer.publicKey,
+      paymentId,
+      customer1,
+      sol(1)
+    );
+
+    const joined = await getEvents(program, signature, "participantJoined");
+    const [paid] = await getEvents(program, signature, "participantPaid");
+
+    expect(joined).to.be.empty;
+
+    expect(paid.collectedAmount.toNumber()).equal(sol(2).toNumber());
+    expect(paid.remainingAmount.toNumber()).equal(sol(1).toNumber());
+  });
+
+  it("Completing contribution carries the final balances", async () => {
+    const signature = await participate(
+      program,
+      issuer.publicKey,
+      paymentId,
+      customer2,
+      sol(1)
+    );
+
+    const [joined] = await getEvents(program, signature, "participantJoined");
+    const [completed] = await getEvents(program, signature, "vaultCompleted");
+
+    expect(joined.participantCount).equal(2);
+
+    expect(completed.version).equal(eventVersion);
+    expect(completed.amount.toNumber()).equal(sol(1).toNumber());
+    expect(completed.collectedAmount.toNumber()).equal(sol(3).toNumber());
+    expect(completed.remainingAmount.toNumber()).equal(0);
+  });
+
+  it("Release carries the payout of every recipient", async () => {
+    const signature = await closeVault(program, issuer, paymentId);
+
+    const [released] = await getEvents(program, signature, "vaultReleased");
+
+    expect(released.version).equal(eventVersion);
+    expect(released.mint).to.be.null;
+    expect(released.amount.toNumber()).equal(sol(3).toNumber());
+    expect(released.payouts.length).equal(1);
+    expect(released.payouts[0].recipient.equals(issuer.publicKey)).to.be.true;
+    expect(released.payouts[0].amount.toNumber()).equal(sol(3).toNumber());
+  });
+});
@@ -0,0 +1,109 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  authorityRent,
+  chainTime,
+  claimRefund,
+  expectError,
+  initializeVault,
+  participate,
+  sol,
+} from "./helpers";
+import { getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi payment deadline", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const amount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+  let expiresAt: number;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+
+    expiresAt = (await chainTime(provider)) + 5;
+
+    await initializeVault(program, issuer, paymentId, {
+      totalAmount: amount,
+      expiresAt: new anchor.BN(expiresAt),
+    });
+  });
+
+  it("Vault can't be created with a deadline in the past", async () => {
+    await expectError(
+      initializeVault(program, issuer, paymentId + 1, {
+        totalAmount: amount,
+        expiresAt: new anchor.BN(1),
+      }),
+      "InvalidExpiration"
+    );
+  });
+
+  it("Customer A participate 4 SOL before the deadline", async () => {
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(4));
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.expiresAt.toNumber()).equal(expiresAt);
+  });
+
+  it("Customer A can't claim a refund before the deadline", async () => {
+    await expectError(
+      claimRefund(program, issuer.publicKey, paymentId, customerA),
+      "RefundNotClaimable"
+    );
+  });
+
+  it("Customer B can't participate after the deadline", async () => {
+    while ((await chainTime(provider)) <= expiresAt) {
+      await new Promise((resolve) => setTimeout(resolve, 1000));
+    }
+
+    await expectError(
+      participate(program, issuer.publicKey, paymentId, customerB, sol(2)),
+      "VaultIsExpired"
+    );
+  });
+
+  it("Customer A claim his refund after the deadline", async () => {
+    const participantVault = getParticipantPdas(
+      issuer.publicKey,
+      customerA.publicKey,
+      paymentId,
+      program.programId
+    ).vault;
+    const balanceBefore = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+
+    await claimRefund(program, issuer.publicKey, paymentId, customerA);
+
+    const balanceAfter = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+
+    expect(balanceAfter - balanceBefore).to.be.greaterThan(
+      4 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(await provider.connection.getAccountInfo(participantVault)).to.be
+      .null;
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      await authorityRent(provider)
+    );
+  });
+});
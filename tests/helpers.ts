@@ -0,0 +1,290 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert, expect } from "chai";
+import { Divi } from "../target/types/divi";
+import {
+  getIssuerProfilePda,
+  getMetadataPda,
+  getParticipantPdas,
+  getVaultPdas,
+} from "./pdas";
+
+export function sol(amount: number) {
+  return new anchor.BN(amount * anchor.web3.LAMPORTS_PER_SOL);
+}
+
+export async function expectError(promise: Promise<unknown>, code: string) {
+  try {
+    await promise;
+
+    assert.fail(`Expected ${code}`);
+  } catch (err) {
+    expect(err).to.be.instanceOf(anchor.AnchorError);
+    assert.strictEqual((err as anchor.AnchorError).error.errorCode.code, code);
+  }
+}
+
+export async function chainTime(provider: anchor.Provider) {
+  const slot = await provider.connection.getSlot();
+
+  return await provider.connection.getBlockTime(slot);
+}
+
+/**
+ * Rent funded by the issuer in the vault authority of a native SOL vault
+ */
+export async function authorityRent(provider: anchor.Provider) {
+  return await provider.connection.getMinimumBalanceForRentExemption(0);
+}
+
+/**
+ * Create a native SOL vault of 10 SOL by default, open to any contribution
+ */
+export async function initializeVault(
+  program: Program<Divi>,
+  issuer: anchor.web3.Keypair,
+  paymentId: number,
+  {
+    totalAmount = sol(10),
+    expiresAt = null,
+    recipient = null,
+    shares = [],
+  }: {
+    totalAmount?: anchor.BN;
+    expiresAt?: anchor.BN | null;
+    recipient?: anchor.web3.PublicKey | null;
+    shares?: { participant: anchor.web3.PublicKey; value: anchor.BN }[];
+  } = {}
+) {
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  return await program.methods
+    .initializeVault(
+      paymentId,
+      totalAmount,
+      expiresAt,
+      recipient,
+      { exact: {} },
+      shares
+    )
+    .accountsStrict({
+      issuer: issuer.publicKey,
+      issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+      vault,
+      vaultAuthority,
+      mint: null,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([issuer])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function participate(
+  program: Program<Divi>,
+  issuer: anchor.web3.PublicKey,
+  paymentId: number,
+  participant: anchor.web3.Keypair,
+  amount: anchor.BN
+) {
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer,
+    paymentId,
+    program.programId
+  );
+
+  return await program.methods
+    .participate(paymentId, amount)
+    .accountsStrict({
+      participant: participant.publicKey,
+      vault,
+      participantVault: getParticipantPdas(
+        issuer,
+        participant.publicKey,
+        paymentId,
+        program.programId
+      ).vault,
+      vaultAuthority,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([participant])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function claimRefund(
+  program: Program<Divi>,
+  issuer: anchor.web3.PublicKey,
+  paymentId: number,
+  participant: anchor.web3.Keypair
+) {
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer,
+    paymentId,
+    program.programId
+  );
+
+  return await program.methods
+    .claimRefund(paymentId)
+    .accountsStrict({
+      participant: participant.publicKey,
+      vault,
+      vaultAuthority,
+      participantVault: getParticipantPdas(
+        issuer,
+        participant.publicKey,
+        paymentId,
+        program.programId
+      ).vault,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([participant])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function cancelPayment(
+  program: Program<Divi>,
+  issuer: anchor.web3.Keypair,
+  paymentId: number
+) {
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  return await program.methods
+    .cancelPayment(paymentId)
+    .accountsStrict({
+      issuer: issuer.publicKey,
+      vault,
+      vaultAuthority,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([issuer])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function approveRelease(
+  program: Program<Divi>,
+  issuer: anchor.web3.PublicKey,
+  paymentId: number,
+  participant: anchor.web3.Keypair
+) {
+  return await program.methods
+    .approveRelease(paymentId)
+    .accountsStrict({
+      participant: participant.publicKey,
+      vault: getVaultPdas(issuer, paymentId, program.programId).vault,
+      participantVault: getParticipantPdas(
+        issuer,
+        participant.publicKey,
+        paymentId,
+        program.programId
+      ).vault,
+    })
+    .signers([participant])
+    .rpc({ commitment: "confirmed" });
+}
+
+export async function openDispute(
+  program: Program<Divi>,
+  issuer: anchor.web3.PublicKey,
+  paymentId: number,
+  participant: anchor.web3.Keypair
+) {
+  return await program.methods
+    .openDispute(paymentId)
+    .accountsStrict({
+      participant: participant.publicKey,
+      vault: getVaultPdas(issuer, paymentId, program.programId).vault,
+      participantVault: getParticipantPdas(
+        issuer,
+        participant.publicKey,
+        paymentId,
+        program.programId
+      ).vault,
+    })
+    .signers([participant])
+    .rpc({ commitment: "confirmed" });
+}
+
+/**
+ * Pay the funded vault out to its single recipient, the issuer by default
+ */
+export async function closeVault(
+  program: Program<Divi>,
+  issuer: anchor.web3.Keypair,
+  paymentId: number,
+  recipient = issuer.publicKey
+) {
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  return await program.methods
+    .closeVault(paymentId)
+    .accountsStrict({
+      issuer: issuer.publicKey,
+      recipient,
+      vault,
+      vaultAuthority,
+      metadata: getMetadataPda(vault, program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([issuer])
+    .rpc({ commitment: "confirmed" });
+}
+
+/**
+ * Close a vault left without participants, its rent going back to the issuer
+ */
+export async function closePaymentVault(
+  program: Program<Divi>,
+  issuer: anchor.web3.Keypair,
+  paymentId: number
+) {
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  return await program.methods
+    .closePaymentVault(paymentId)
+    .accountsStrict({
+      issuer: issuer.publicKey,
+      vault,
+      vaultAuthority,
+      metadata: getMetadataPda(vault, program.programId),
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([issuer])
+    .rpc({ commitment: "confirmed" });
+}
+
+/**
+ * Decode the events of the given name emitted by a confirmed transaction
+ */
+export async function getEvents(
+  program: Program<Divi>,
+  signature: string,
+  name: string
+) {
+  const tx = await program.provider.connection.getTransaction(signature, {
+    commitment: "confirmed",
+    maxSupportedTransactionVersion: 0,
+  });
+
+  return [
+    ...new anchor.EventParser(program.programId, program.coder).parseLogs(
+      tx.meta.logMessages
+    ),
+  ]
+    .filter((event) => event.name === name)
+    .map((event) => event.data);
+}
@@ -0,0 +1,126 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert, expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import { authorityRent } from "./helpers";
+import { getIssuerProfilePda, getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi participation top-up", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const amount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+
+    await program.methods
+      .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+        vault,
+        vaultAuthority,
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    await program.methods
+      .participate(paymentId, new anchor.BN(3 * anchor.web3.LAMPORTS_PER_SOL))
+      .accountsStrict({
+        participant: customerA.publicKey,
+        vault,
+        participantVault: participantVaultOf(customerA),
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customerA])
+      .rpc();
+  });
+
+  it("Customer A add 2 SOL to his participation", async () => {
+    await increase(customerA, 2);
+
+    const ledger = await program.account.participantVault.fetch(
+      participantVaultOf(customerA)
+    );
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(ledger.amount.toNumber()).equal(5 * anchor.web3.LAMPORTS_PER_SOL);
+    expect(vaultData.collectedAmount.toNumber()).equal(
+      5 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      5 * anchor.web3.LAMPORTS_PER_SOL + (await authorityRent(provider))
+    );
+  });
+
+  it("Customer A can't add more than the remaining amount", async () => {
+    try {
+      await increase(customerA, 6);
+
+      assert.fail("Top-up above the remaining amount should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "AmountIsGreaterThanRemainingVaultAmount"
+      );
+    }
+  });
+
+  it("Customer B can't top-up without participating first", async () => {
+    try {
+      await increase(customerB, 1);
+
+      assert.fail("Top-up without participation should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "AccountNotInitialized"
+      );
+    }
+  });
+
+  function participantVaultOf(customer: anchor.web3.Keypair) {
+    return getParticipantPdas(
+      issuer.publicKey,
+      customer.publicKey,
+      paymentId,
+      program.programId
+    ).vault;
+  }
+
+  async function increase(customer: anchor.web3.Keypair, sol: number) {
+    await program.methods
+      .increaseParticipation(
+        paymentId,
+        new anchor.BN(sol * anchor.web3.LAMPORTS_PER_SOL)
+      )
+      .accountsStrict({
+        participant: customer.publicKey,
+        vault,
+        participantVault: participantVaultOf(customer),
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+  }
+});
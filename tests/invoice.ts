@@ -0,0 +1,88 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import { expectError, initializeVault, participate, sol } from "./helpers";
+import { getVaultPdas } from "./pdas";
+
+describe("Divi invoice mode", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const friendA = anchor.web3.Keypair.generate();
+  const friendB = anchor.web3.Keypair.generate();
+  const stranger = anchor.web3.Keypair.generate();
+  const shares = [
+    { participant: friendA.publicKey, value: sol(6) },
+    { participant: friendB.publicKey, value: sol(4) },
+  ];
+  const { vault } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, friendA.publicKey, 100);
+    await airdrop(provider, friendB.publicKey, 100);
+    await airdrop(provider, stranger.publicKey, 100);
+  });
+
+  it("Issuer can't register shares that don't add up to the total", async () => {
+    await expectError(
+      initializeVault(program, issuer, paymentId, {
+        totalAmount: sol(12),
+        shares,
+      }),
+      "InvalidInvoiceShares"
+    );
+  });
+
+  it("Issuer split a 10 SOL bill between friend A and friend B", async () => {
+    await initializeVault(program, issuer, paymentId, { shares });
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.shares.length).equal(2);
+    expect(vaultData.shares.every((share) => !share.isPaid)).to.be.true;
+  });
+
+  it("A stranger can't pay the invoice", async () => {
+    await expectError(
+      participate(program, issuer.publicKey, paymentId, stranger, sol(4)),
+      "ParticipantNotInInvoice"
+    );
+  });
+
+  it("Friend A can't pay less than his share", async () => {
+    await expectError(
+      participate(program, issuer.publicKey, paymentId, friendA, sol(5)),
+      "AmountDoesNotMatchShare"
+    );
+  });
+
+  it("Friend A pay exactly his share, friend B is still outstanding", async () => {
+    await participate(program, issuer.publicKey, paymentId, friendA, sol(6));
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+    const outstanding = vaultData.shares.filter((share) => !share.isPaid);
+
+    expect(outstanding.length).equal(1);
+    expect(outstanding[0].participant.equals(friendB.publicKey)).to.be.true;
+    expect(vaultData.status).to.deep.equal({ open: {} });
+  });
+
+  it("Friend B pay his share and finalize the vault", async () => {
+    await participate(program, issuer.publicKey, paymentId, friendB, sol(4));
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.shares.every((share) => share.isPaid)).to.be.true;
+    expect(vaultData.status).to.deep.equal({ funded: {} });
+  });
+});
@@ -0,0 +1,327 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  authorityRent,
+  closeVault,
+  expectError,
+  initializeVault,
+  sol,
+} from "./helpers";
+import { getIssuerProfilePda, getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi pay ledger", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const amount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+
+    await program.methods
+      .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+        vault,
+        vaultAuthority,
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+  });
+
+  it("Customer A pay twice, contributions are accumulated", async () => {
+    await pay(program, issuer.publicKey, paymentId, customerA, sol(3));
+    await pay(program, issuer.publicKey, paymentId, customerA, sol(1));
+
+    const ledger = await program.account.participantVault.fetch(
+      getParticipantPdas(
+        issuer.publicKey,
+        customerA.publicKey,
+        paymentId,
+        program.programId
+      ).vault
+    );
+
+    expect(ledger.participant.equals(customerA.publicKey)).to.be.true;
+    expect(ledger.issuer.equals(issuer.publicKey)).to.be.true;
+    expect(ledger.amount.toNumber()).equal(4 * anchor.web3.LAMPORTS_PER_SOL);
+  });
+
+  it("Customer B pay, contributions are reconstructed from the ledger", async () => {
+    await pay(program, issuer.publicKey, paymentId, customerB, sol(2));
+
+    const ledgers = await program.account.participantVault.all([
+      {
+        memcmp: {
+          offset: 8,
+          bytes: issuer.publicKey.toBase58(),
+        },
+      },
+    ]);
+    const total = ledgers.reduce(
+      (sum, ledger) => sum + ledger.account.amount.toNumber(),
+      0
+    );
+
+    expect(ledgers.length).equal(2);
+    expect(total + (await authorityRent(provider))).equal(
+      await provider.connection.getBalance(vaultAuthority)
+    );
+  });
+
+  it("Issuer refund every payer from the vault authority", async () => {
+    const balanceBefore = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+
+    for (const customer of [customerA, customerB]) {
+      const pdas = getParticipantPdas(
+        issuer.publicKey,
+        customer.publicKey,
+        paymentId,
+        program.programId
+      );
+
+      await program.methods
+        .refundParticipant(paymentId)
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          participant: customer.publicKey,
+          vault,
+          vaultAuthority,
+          participantVault: pdas.vault,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+    }
+
+    const balanceAfter = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+
+    expect(balanceAfter - balanceBefore).to.be.greaterThan(
+      4 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      await authorityRent(provider)
+    );
+  });
+
+  it("Issuer cancel the payment once every payer is refunded", async () => {
+    await program.methods
+      .cancelPayment(paymentId)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultAccount.status).to.deep.equal({ cancelled: {} });
+    expect(vaultAccount.participantCount).equal(0);
+  });
+
+});
+
+describe("Divi pay ledger rent", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customer = anchor.web3.Keypair.generate();
+  const participantVault = getParticipantPdas(
+    issuer.publicKey,
+    customer.publicKey,
+    paymentId,
+    program.programId
+  ).vault;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customer.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId);
+    await pay(program, issuer.publicKey, paymentId, customer, sol(10));
+  });
+
+  it("Customer can't close his ledger while the vault is open", async () => {
+    await expectError(closeParticipantVault(), "VaultIsNotClosed");
+  });
+
+  it("Customer get the ledger rent back once the vault is released", async () => {
+    await closeVault(program, issuer, paymentId);
+
+    const rent = await provider.connection.getBalance(participantVault);
+    const balanceBefore = await provider.connection.getBalance(
+      customer.publicKey
+    );
+
+    await closeParticipantVault();
+
+    const balanceAfter = await provider.connection.getBalance(
+      customer.publicKey
+    );
+
+    expect(await provider.connection.getAccountInfo(participantVault)).to.be
+      .null;
+    // Rent minus the transaction fee
+    expect(balanceAfter - balanceBefore).to.be.closeTo(rent, 10000);
+  });
+
+  async function closeParticipantVault() {
+    return program.methods
+      .closeParticipantVault(paymentId)
+      .accountsStrict({
+        participant: customer.publicKey,
+        vault: getVaultPdas(issuer.publicKey, paymentId, program.programId)
+          .vault,
+        participantVault,
+      })
+      .signers([customer])
+      .rpc();
+  }
+});
+
+describe("Divi pay ledger reuse", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+
+    // Customer A keeps his ledger once the first vault is released
+    await initializeVault(program, issuer, paymentId);
+    await pay(program, issuer.publicKey, paymentId, customerA, sol(10));
+    await closeVault(program, issuer, paymentId);
+
+    await initializeVault(program, issuer, paymentId);
+    await pay(program, issuer.publicKey, paymentId, customerB, sol(3));
+  });
+
+  it("Ledger of the closed vault can't withdraw from the new vault", async () => {
+    await expectError(withdraw(customerA, sol(3)), "InvalidParticipantVault");
+
+    expect(await provider.connection.getBalance(vaultAuthority)).to.be.gte(
+      3 * anchor.web3.LAMPORTS_PER_SOL
+    );
+  });
+
+  it("Ledger of the closed vault starts over on a new payment", async () => {
+    await pay(program, issuer.publicKey, paymentId, customerA, sol(2));
+
+    const ledger = await program.account.participantVault.fetch(
+      getParticipantPdas(
+        issuer.publicKey,
+        customerA.publicKey,
+        paymentId,
+        program.programId
+      ).vault
+    );
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(ledger.amount.toNumber()).equal(2 * anchor.web3.LAMPORTS_PER_SOL);
+    expect(ledger.vaultNonce.eq(vaultData.nonce)).to.be.true;
+    expect(vaultData.participantCount).equal(2);
+    expect(vaultData.collectedAmount.toNumber()).equal(
+      5 * anchor.web3.LAMPORTS_PER_SOL
+    );
+
+    // Both participants can leave, the count goes back to zero
+    await withdraw(customerA, sol(2));
+    await withdraw(customerB, sol(3));
+
+    const vaultAfter = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultAfter.participantCount).equal(0);
+    expect(vaultAfter.collectedAmount.toNumber()).equal(0);
+  });
+
+  async function withdraw(customer: anchor.web3.Keypair, amount: anchor.BN) {
+    return program.methods
+      .withdrawParticipation(paymentId, amount)
+      .accountsStrict({
+        participant: customer.publicKey,
+        vault,
+        vaultAuthority,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customer.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+  }
+});
+
+async function pay(
+  program: Program<Divi>,
+  issuer: anchor.web3.PublicKey,
+  paymentId: number,
+  payer: anchor.web3.Keypair,
+  amount: anchor.BN
+) {
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer,
+    paymentId,
+    program.programId
+  );
+
+  return program.methods
+    .pay(paymentId, amount)
+    .accountsStrict({
+      payer: payer.publicKey,
+      issuer,
+      vault,
+      vaultAuthority,
+      participantVault: getParticipantPdas(
+        issuer,
+        payer.publicKey,
+        paymentId,
+        program.programId
+      ).vault,
+      systemProgram: anchor.web3.SystemProgram.programId,
+    })
+    .signers([payer])
+    .rpc();
+}
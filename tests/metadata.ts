@@ -0,0 +1,140 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  closeVault,
+  expectError,
+  getEvents,
+  initializeVault,
+  participate,
+  sol,
+} from "./helpers";
+import { getMetadataPda, getVaultPdas } from "./pdas";
+
+describe("Divi payment metadata", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+  const metadata = getMetadataPda(vault, program.programId);
+
+  const invoice = {
+    title: "Ski trip",
+    description: "Chalet rental for the weekend",
+    currency: "EUR",
+    invoiceReference: "INV-2024-042",
+    uri: "https://example.com/invoices/42.pdf",
+  };
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId);
+  });
+
+  it("Issuer describe the payment", async () => {
+    await setMetadata({ ...invoice, uri: null });
+    const signature = await setMetadata(invoice);
+
+    const [updated] = await getEvents(program, signature, "metadataUpdated");
+    const metadataData = await program.account.paymentMetadata.fetch(metadata);
+
+    expect(metadataData.vault.equals(vault)).to.be.true;
+    expect(metadataData.title).equal(invoice.title);
+    expect(metadataData.description).equal(invoice.description);
+    expect(metadataData.currency).equal(invoice.currency);
+    expect(metadataData.invoiceReference).equal(invoice.invoiceReference);
+    expect(metadataData.uri).equal(invoice.uri);
+    expect(updated.amount.toNumber()).equal(sol(10).toNumber());
+    expect(updated.metadata.title).equal(invoice.title);
+  });
+
+  it("Issuer can't exceed the length limits", async () => {
+    await expectError(
+      setMetadata({ ...invoice, title: "a".repeat(65) }),
+      "TitleTooLong"
+    );
+    await expectError(
+      setMetadata({ ...invoice, description: "a".repeat(257) }),
+      "DescriptionTooLong"
+    );
+    await expectError(
+      setMetadata({ ...invoice, currency: "a".repeat(11) }),
+      "StringTooLong"
+    );
+  });
+
+  it("Issuer can't change the metadata once the vault is funded", async () => {
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(1));
+
+    await expectError(
+      setMetadata({ ...invoice, title: "Changed" }),
+      "MetadataIsLocked"
+    );
+  });
+
+  it("Issuer can't close the vault without its metadata", async () => {
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(9));
+
+    await expectError(
+      program.methods
+        .closeVault(paymentId)
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          recipient: issuer.publicKey,
+          vault,
+          vaultAuthority,
+          metadata: anchor.web3.Keypair.generate().publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc(),
+      "ConstraintSeeds"
+    );
+  });
+
+  it("Issuer get the metadata rent back when closing the vault", async () => {
+
+    const rent = await provider.connection.getBalance(metadata);
+    const balanceBefore = await provider.connection.getBalance(
+      issuer.publicKey
+    );
+
+    await closeVault(program, issuer, paymentId);
+
+    const balanceAfter = await provider.connection.getBalance(
+      issuer.publicKey
+    );
+
+    expect(await provider.connection.getAccountInfo(metadata)).to.be.null;
+    expect(balanceAfter - balanceBefore).to.be.greaterThan(
+      sol(10).toNumber() + rent
+    );
+  });
+
+  async function setMetadata(
+    args: Parameters<typeof program.methods.setMetadata>[1]
+  ) {
+    return await program.methods
+      .setMetadata(paymentId, args)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+        metadata,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc({ commitment: "confirmed" });
+  }
+});
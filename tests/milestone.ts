@@ -0,0 +1,279 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  approveRelease,
+  authorityRent,
+  chainTime,
+  expectError,
+  getEvents,
+  initializeVault,
+  openDispute,
+  participate,
+  sol,
+} from "./helpers";
+import { getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi milestone releases", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const customerC = anchor.web3.Keypair.generate();
+  const arbiter = anchor.web3.Keypair.generate();
+  const agency = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+    await airdrop(provider, customerC.publicKey, 100);
+    await airdrop(provider, arbiter.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId, {
+      recipient: agency.publicKey,
+    });
+  });
+
+  it("Issuer define a 4 SOL deposit approved by 2 contributors", async () => {
+    await program.methods
+      .setMilestones(paymentId, [{ amount: sol(4), approvalsRequired: 2 }])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+      })
+      .signers([issuer])
+      .rpc();
+    await program.methods
+      .setArbiter(paymentId, arbiter.publicKey, null)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+      })
+      .signers([issuer])
+      .rpc();
+
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(5));
+    await participate(program, issuer.publicKey, paymentId, customerB, sol(3));
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.milestones.length).equal(1);
+    expect(vaultData.status).to.deep.equal({ open: {} });
+  });
+
+  it("Customer A can't approve the deposit twice", async () => {
+    const signature = await approve(customerA);
+
+    const [approved] = await getEvents(program, signature, "milestoneApproved");
+
+    expect(approved.participant.equals(customerA.publicKey)).to.be.true;
+    expect(approved.index).equal(0);
+    expect(approved.amount.toNumber()).equal(sol(4).toNumber());
+    expect(approved.approvals).equal(1);
+
+    await expectError(approve(customerA), "MilestoneAlreadyApproved");
+  });
+
+  it("Issuer can't release the deposit before the vault is funded", async () => {
+    // A contributor joining after the release would be refunded pro-rata
+    await expectError(release(), "VaultIsNotFinalized");
+
+    await participate(program, issuer.publicKey, paymentId, customerC, sol(2));
+
+    await expectError(release(), "MilestoneNotApproved");
+  });
+
+  it("Customer B approve and the issuer release the deposit", async () => {
+    await approve(customerB);
+    await release();
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(await provider.connection.getBalance(agency.publicKey)).equal(
+      4 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(vaultData.releasedAmount.toNumber()).equal(
+      4 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(vaultData.milestones[0].isReleased).to.be.true;
+  });
+
+  it("Customer A is refunded pro-rata of what stays in the vault", async () => {
+    await openDispute(program, issuer.publicKey, paymentId, customerA);
+    await program.methods
+      .resolveDispute(paymentId, { refund: {} })
+      .accountsStrict({
+        arbiter: arbiter.publicKey,
+        vault,
+      })
+      .signers([arbiter])
+      .rpc();
+
+    const balanceBefore = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+
+    await program.methods
+      .refundParticipant(paymentId)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        participant: customerA.publicKey,
+        vault,
+        vaultAuthority,
+        participantVault: participantVaultOf(customerA),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const balanceAfter = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+    const rent = await provider.connection.getMinimumBalanceForRentExemption(
+      program.account.participantVault.size
+    );
+
+    // 5 of the 10 SOL contributed, 6 SOL still escrowed
+    expect(balanceAfter - balanceBefore).equal(
+      3 * anchor.web3.LAMPORTS_PER_SOL + rent
+    );
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      3 * anchor.web3.LAMPORTS_PER_SOL + (await authorityRent(provider))
+    );
+  });
+
+  function participantVaultOf(customer: anchor.web3.Keypair) {
+    return getParticipantPdas(
+      issuer.publicKey,
+      customer.publicKey,
+      paymentId,
+      program.programId
+    ).vault;
+  }
+
+  async function approve(customer: anchor.web3.Keypair) {
+    return await program.methods
+      .approveMilestone(paymentId, 0)
+      .accountsStrict({
+        participant: customer.publicKey,
+        vault,
+        participantVault: participantVaultOf(customer),
+      })
+      .signers([customer])
+      .rpc({ commitment: "confirmed" });
+  }
+
+  async function release() {
+    await program.methods
+      .releaseMilestone(paymentId, 0)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        recipient: agency.publicKey,
+        vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+  }
+});
+
+describe("Divi milestone releases under a release quorum", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customer = anchor.web3.Keypair.generate();
+  const agency = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customer.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId, {
+      recipient: agency.publicKey,
+    });
+    await program.methods
+      .setMilestones(paymentId, [{ amount: sol(4), approvalsRequired: 1 }])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+      })
+      .signers([issuer])
+      .rpc();
+    await program.methods
+      .setReleaseQuorum(
+        paymentId,
+        { contributors: { approvals: 1 } },
+        new anchor.BN((await chainTime(provider)) + 3600)
+      )
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+      })
+      .signers([issuer])
+      .rpc();
+
+    await participate(program, issuer.publicKey, paymentId, customer, sol(10));
+    await program.methods
+      .approveMilestone(paymentId, 0)
+      .accountsStrict({
+        participant: customer.publicKey,
+        vault,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customer.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+      })
+      .signers([customer])
+      .rpc();
+  });
+
+  it("Issuer can't release an approved milestone before the quorum", async () => {
+    await expectError(release(), "ReleaseNotApproved");
+  });
+
+  it("Issuer release the milestone once the quorum approved", async () => {
+    await approveRelease(program, issuer.publicKey, paymentId, customer);
+    await release();
+
+    expect(await provider.connection.getBalance(agency.publicKey)).equal(
+      4 * anchor.web3.LAMPORTS_PER_SOL
+    );
+  });
+
+  async function release() {
+    await program.methods
+      .releaseMilestone(paymentId, 0)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        recipient: agency.publicKey,
+        vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+  }
+});
@@ -0,0 +1,99 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  cancelPayment,
+  claimRefund,
+  closePaymentVault,
+  expectError,
+  initializeVault,
+  participate,
+  sol,
+} from "./helpers";
+import { getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi participant count", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId);
+  });
+
+  it("Each participant is counted once", async () => {
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(2));
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(1));
+    await participate(program, issuer.publicKey, paymentId, customerB, sol(3));
+
+    await expectParticipantCount(2);
+  });
+
+  it("Participant withdrawing everything leaves the vault", async () => {
+    await program.methods
+      .withdrawParticipation(
+        paymentId,
+        new anchor.BN(3 * anchor.web3.LAMPORTS_PER_SOL)
+      )
+      .accountsStrict({
+        participant: customerB.publicKey,
+        vault,
+        vaultAuthority,
+        participantVault: participantVault(customerB),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customerB])
+      .rpc();
+
+    await expectParticipantCount(1);
+  });
+
+  it("Issuer cancel the payment without listing the participants", async () => {
+    await cancelPayment(program, issuer, paymentId);
+
+    await expectError(
+      closePaymentVault(program, issuer, paymentId),
+      "NotAllParticipantsRefunded"
+    );
+  });
+
+  it("Issuer close the vault once customer A claimed his refund", async () => {
+    await claimRefund(program, issuer.publicKey, paymentId, customerA);
+
+    await expectParticipantCount(0);
+    await closePaymentVault(program, issuer, paymentId);
+
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+  });
+
+  function participantVault(customer: anchor.web3.Keypair) {
+    return getParticipantPdas(
+      issuer.publicKey,
+      customer.publicKey,
+      paymentId,
+      program.programId
+    ).vault;
+  }
+
+  async function expectParticipantCount(count: number) {
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.participantCount).equal(count);
+  }
+});
@@ -0,0 +1,113 @@
+import * as anchor from "@coral-xyz/anchor";
+import {
+  ISSUER_PROFILE,
+  PARTICIPANT_VAULT,
+  PARTICIPANT_VAULT_AUTHORITY,
+  PAYMENT_METADATA,
+  VAULT,
+  VAULT_AUTHORITY,
+} from "./constants";
+
+export function getVaultPdas(
+  issuer: anchor.web3.PublicKey,
+  paymentId: number,
+  programId: anchor.web3.PublicKey
+) {
+  const paymentIdBuffer = new anchor.BN(paymentId).toArrayLike(Buffer, "le", 4);
+
+  const [vault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(VAULT), issuer.toBuffer(), paymentIdBuffer],
+    programId
+  );
+
+  const [vaultAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(VAULT_AUTHORITY),
+      issuer.toBuffer(),
+      paymentIdBuffer,
+    ],
+    programId
+  );
+
+  return {
+    vault,
+    vaultAuthority,
+  };
+}
+
+export function getParticipantPdas(
+  issuer: anchor.web3.PublicKey,
+  participant: anchor.web3.PublicKey,
+  paymentId: number,
+  programId: anchor.web3.PublicKey
+) {
+  const { vault: paymentVault } = getVaultPdas(issuer, paymentId, programId);
+
+  const [vault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(PARTICIPANT_VAULT),
+      paymentVault.toBuffer(),
+      participant.toBuffer(),
+    ],
+    programId
+  );
+
+  return {
+    vault,
+  };
+}
+
+export function getLegacyParticipantPdas(
+  participant: anchor.web3.PublicKey,
+  paymentId: number,
+  programId: anchor.web3.PublicKey
+) {
+  const paymentIdBuffer = new anchor.BN(paymentId).toArrayLike(Buffer, "le", 4);
+
+  const [vault] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(PARTICIPANT_VAULT),
+      participant.toBuffer(),
+      paymentIdBuffer,
+    ],
+    programId
+  );
+
+  const [vaultAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
+    [
+      anchor.utils.bytes.utf8.encode(PARTICIPANT_VAULT_AUTHORITY),
+      participant.toBuffer(),
+      paymentIdBuffer,
+    ],
+    programId
+  );
+
+  return {
+    vault,
+    vaultAuthority,
+  };
+}
+
+export function getIssuerProfilePda(
+  issuer: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) {
+  const [issuerProfile] = anchor.web3.PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(ISSUER_PROFILE), issuer.toBuffer()],
+    programId
+  );
+
+  return issuerProfile;
+}
+
+export function getMetadataPda(
+  vault: anchor.web3.PublicKey,
+  programId: anchor.web3.PublicKey
+) {
+  const [metadata] = anchor.web3.PublicKey.findProgramAddressSync(
+    [anchor.utils.bytes.utf8.encode(PAYMENT_METADATA), vault.toBuffer()],
+    programId
+  );
+
+  return metadata;
+}
@@ -0,0 +1,117 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import { FIRST_PROFILE_PAYMENT_ID } from "./constants";
+import { expectError, getEvents, initializeVault } from "./helpers";
+import { getIssuerProfilePda, getMetadataPda, getVaultPdas } from "./pdas";
+
+describe("Divi payment id allocated by the issuer profile", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const issuer = anchor.web3.Keypair.generate();
+  const issuerProfile = getIssuerProfilePda(
+    issuer.publicKey,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+  });
+
+  it("First vault gets the first profile payment id and creates the profile", async () => {
+    // A vault created with a payment id picked by the issuer doesn't collide
+    await initializeVault(program, issuer, 0);
+
+    const paymentId = await createVault();
+
+    expect(paymentId).equal(FIRST_PROFILE_PAYMENT_ID);
+
+    const profile = await program.account.issuerProfile.fetch(issuerProfile);
+
+    expect(profile.issuer.equals(issuer.publicKey)).to.be.true;
+    expect(profile.nextPaymentId).equal(FIRST_PROFILE_PAYMENT_ID + 1);
+    // Both vaults are counted, whichever instruction created them
+    expect(profile.vaultCount.toNumber()).equal(2);
+  });
+
+  it("Next vaults get increasing payment ids", async () => {
+    expect(await createVault()).equal(FIRST_PROFILE_PAYMENT_ID + 1);
+    expect(await createVault()).equal(FIRST_PROFILE_PAYMENT_ID + 2);
+
+    const { vault } = getVaultPdas(
+      issuer.publicKey,
+      FIRST_PROFILE_PAYMENT_ID + 2,
+      program.programId
+    );
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.paymentId).equal(FIRST_PROFILE_PAYMENT_ID + 2);
+    expect(vaultData.totalAmount.toNumber()).equal(
+      10 * anchor.web3.LAMPORTS_PER_SOL
+    );
+  });
+
+  it("Issuer can't pick a payment id allocated by the profile", async () => {
+    await expectError(
+      initializeVault(program, issuer, FIRST_PROFILE_PAYMENT_ID + 3),
+      "InvalidPaymentId"
+    );
+
+    // The issuer picked ids keep working along the profile
+    await initializeVault(program, issuer, 1);
+
+    expect(await createVault()).equal(FIRST_PROFILE_PAYMENT_ID + 3);
+  });
+
+  /**
+   * Create a vault with the next payment id and return the id read from the
+   * `VaultCreated` event
+   */
+  async function createVault() {
+    const profile = await program.account.issuerProfile.fetchNullable(
+      issuerProfile
+    );
+    const { vault, vaultAuthority } = getVaultPdas(
+      issuer.publicKey,
+      Math.max(profile?.nextPaymentId ?? 0, FIRST_PROFILE_PAYMENT_ID),
+      program.programId
+    );
+
+    const signature = await program.methods
+      .createVault(
+        new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL),
+        null,
+        null,
+        { exact: {} },
+        [],
+        {
+          title: "Team dinner",
+          description: "",
+          currency: "EUR",
+          invoiceReference: "",
+          uri: null,
+        }
+      )
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile,
+        vault,
+        vaultAuthority,
+        metadata: getMetadataPda(vault, program.programId),
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc({ commitment: "confirmed" });
+
+    const [event] = await getEvents(program, signature, "vaultCreated");
+
+    expect(event.metadata.title).equal("Team dinner");
+
+    return event.paymentId as number;
+  }
+});
@@ -0,0 +1,89 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  closeVault,
+  expectError,
+  initializeVault,
+  participate,
+} from "./helpers";
+import { getVaultPdas } from "./pdas";
+
+describe("Divi payout recipient", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const amount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const restaurant = anchor.web3.Keypair.generate();
+  const landlord = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId, {
+      totalAmount: amount,
+      recipient: restaurant.publicKey,
+    });
+  });
+
+  it("Issuer change the recipient before any contribution", async () => {
+    await setRecipient(landlord.publicKey);
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.recipient.equals(landlord.publicKey)).to.be.true;
+    expect(vaultData.hasContributions).to.be.false;
+  });
+
+  it("Customer A pay the whole amount and lock the recipient", async () => {
+    await participate(program, issuer.publicKey, paymentId, customerA, amount);
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultData.hasContributions).to.be.true;
+  });
+
+  it("Issuer can't change the recipient anymore", async () => {
+    await expectError(setRecipient(restaurant.publicKey), "RecipientIsLocked");
+  });
+
+  it("Issuer can't close the vault to another recipient", async () => {
+    await expectError(
+      closeVault(program, issuer, paymentId, issuer.publicKey),
+      "InvalidRecipient"
+    );
+  });
+
+  it("Issuer close the vault, the landlord receive the funds", async () => {
+    await closeVault(program, issuer, paymentId, landlord.publicKey);
+
+    expect(await provider.connection.getBalance(landlord.publicKey)).equal(
+      amount.toNumber()
+    );
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(0);
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+  });
+
+  async function setRecipient(recipient: anchor.web3.PublicKey) {
+    await program.methods
+      .setRecipient(paymentId, recipient)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+      })
+      .signers([issuer])
+      .rpc();
+  }
+});
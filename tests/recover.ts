@@ -0,0 +1,143 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  authorityRent,
+  closePaymentVault,
+  expectError,
+  getEvents,
+  initializeVault,
+  participate,
+  sol,
+} from "./helpers";
+import { getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi safe vault closing", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const donor = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, donor.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId);
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(4));
+
+    await donate(1);
+  });
+
+  it("Issuer can't recover the authority of an open vault", async () => {
+    await expectError(recoverVaultAuthority(), "VaultIsNotClosed");
+  });
+
+  it("Issuer can't close the vault before customer A is refunded", async () => {
+    await expectError(
+      closePaymentVault(program, issuer, paymentId),
+      "NotAllParticipantsRefunded"
+    );
+  });
+
+  it("Issuer close the vault once customer A is refunded", async () => {
+    await program.methods
+      .refundParticipant(paymentId)
+      .accountsStrict({
+        vault,
+        vaultAuthority,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customerA.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        issuer: issuer.publicKey,
+        participant: customerA.publicKey,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const balanceBefore = await provider.connection.getBalance(
+      issuer.publicKey
+    );
+
+    const signature = await closePaymentVault(program, issuer, paymentId);
+
+    const [closed] = await getEvents(program, signature, "vaultClosed");
+    const completed = await getEvents(program, signature, "vaultCompleted");
+
+    expect(closed.amount.toNumber()).equal(
+      sol(1).toNumber() + (await authorityRent(provider))
+    );
+    expect(completed).to.be.empty;
+
+    // The donation is swept with the vault rent
+    expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(0);
+    expect(
+      (await provider.connection.getBalance(issuer.publicKey)) - balanceBefore
+    ).greaterThan(anchor.web3.LAMPORTS_PER_SOL);
+  });
+
+  it("Issuer recover lamports sent to the authority of the closed vault", async () => {
+    await donate(2);
+
+    const balanceBefore = await provider.connection.getBalance(
+      issuer.publicKey
+    );
+
+    const signature = await recoverVaultAuthority();
+
+    const [recovered] = await getEvents(
+      program,
+      signature,
+      "vaultAuthorityRecovered"
+    );
+
+    expect(recovered.mint).to.be.null;
+    expect(recovered.amount.toNumber()).equal(sol(2).toNumber());
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(0);
+    expect(
+      (await provider.connection.getBalance(issuer.publicKey)) - balanceBefore
+    ).greaterThan(anchor.web3.LAMPORTS_PER_SOL);
+  });
+
+  async function donate(amount: number) {
+    await provider.sendAndConfirm(
+      new anchor.web3.Transaction().add(
+        anchor.web3.SystemProgram.transfer({
+          fromPubkey: donor.publicKey,
+          toPubkey: vaultAuthority,
+          lamports: amount * anchor.web3.LAMPORTS_PER_SOL,
+        })
+      ),
+      [donor]
+    );
+  }
+
+  async function recoverVaultAuthority() {
+    return await program.methods
+      .recoverVaultAuthority(paymentId)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc({ commitment: "confirmed" });
+  }
+});
@@ -0,0 +1,268 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import * as fs from "fs";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import { expectError, initializeVault, participate, sol } from "./helpers";
+import {
+  getLegacyParticipantPdas,
+  getParticipantPdas,
+  getVaultPdas,
+} from "./pdas";
+
+describe("Divi participant vaults scoped by vault", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuerA = anchor.web3.Keypair.generate();
+  const issuerB = anchor.web3.Keypair.generate();
+  const customer = anchor.web3.Keypair.generate();
+  const newcomer = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await airdrop(provider, issuerA.publicKey, 100);
+    await airdrop(provider, issuerB.publicKey, 100);
+    await airdrop(provider, customer.publicKey, 100);
+    await airdrop(provider, newcomer.publicKey, 100);
+
+    await initializeVault(program, issuerA, paymentId);
+    await initializeVault(program, issuerB, paymentId);
+  });
+
+  it("Customer join two vaults sharing the same payment id", async () => {
+    await participate(program, issuerA.publicKey, paymentId, customer, sol(2));
+    await participate(program, issuerB.publicKey, paymentId, customer, sol(3));
+
+    const ledgerA = await program.account.participantVault.fetch(
+      getParticipantPdas(
+        issuerA.publicKey,
+        customer.publicKey,
+        paymentId,
+        program.programId
+      ).vault
+    );
+    const ledgerB = await program.account.participantVault.fetch(
+      getParticipantPdas(
+        issuerB.publicKey,
+        customer.publicKey,
+        paymentId,
+        program.programId
+      ).vault
+    );
+
+    expect(ledgerA.amount.toNumber()).equal(2 * anchor.web3.LAMPORTS_PER_SOL);
+    expect(ledgerB.amount.toNumber()).equal(3 * anchor.web3.LAMPORTS_PER_SOL);
+  });
+
+  it("Newcomer can't migrate without a legacy participant vault", async () => {
+    const { vault, vaultAuthority } = getVaultPdas(
+      issuerA.publicKey,
+      paymentId,
+      program.programId
+    );
+    const legacyPdas = getLegacyParticipantPdas(
+      newcomer.publicKey,
+      paymentId,
+      program.programId
+    );
+
+    await expectError(
+      program.methods
+        .migrateParticipantVault(paymentId)
+        .accountsStrict({
+          participant: newcomer.publicKey,
+          issuer: issuerA.publicKey,
+          vault,
+          legacyParticipantVault: legacyPdas.vault,
+          legacyParticipantVaultAuthority: legacyPdas.vaultAuthority,
+          vaultAuthority,
+          participantVault: getParticipantPdas(
+            issuerA.publicKey,
+            newcomer.publicKey,
+            paymentId,
+            program.programId
+          ).vault,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([newcomer])
+        .rpc(),
+      "InvalidParticipantVault"
+    );
+  });
+});
+
+describe("Divi legacy participant vault migration", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  // Legacy accounts of these wallets are loaded by the validator from
+  // `tests/fixtures`, payment 7 gets a vault in the current layout and payment
+  // 8 is a legacy vault
+  const issuer = loadKeypair("legacy-issuer");
+  const alice = loadKeypair("legacy-alice");
+  const bob = loadKeypair("legacy-bob");
+  const paymentId = 7;
+  const legacyPaymentId = 8;
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, alice.publicKey, 100);
+    await airdrop(provider, bob.publicKey, 100);
+
+    await initializeVault(program, issuer, paymentId);
+  });
+
+  it("Customer migrate his legacy escrow into the vault", async () => {
+    const legacyPdas = getLegacyParticipantPdas(
+      alice.publicKey,
+      paymentId,
+      program.programId
+    );
+    const authorityBefore = await provider.connection.getBalance(
+      vaultAuthority
+    );
+
+    await migrate(alice, paymentId);
+
+    const ledger = await program.account.participantVault.fetch(
+      participantVaultOf(alice, paymentId)
+    );
+    const vaultData = await program.account.paymentVault.fetch(vault);
+    const authorityAfter = await provider.connection.getBalance(
+      vaultAuthority
+    );
+
+    expect(ledger.amount.toNumber()).equal(2 * anchor.web3.LAMPORTS_PER_SOL);
+    expect(ledger.vaultNonce.eq(vaultData.nonce)).to.be.true;
+    expect(vaultData.collectedAmount.toNumber()).equal(
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(vaultData.participantCount).equal(1);
+    expect(authorityAfter - authorityBefore).equal(
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(await provider.connection.getAccountInfo(legacyPdas.vault)).to.be
+      .null;
+    expect(await provider.connection.getAccountInfo(legacyPdas.vaultAuthority))
+      .to.be.null;
+  });
+
+  it("Escrow over the remaining amount is refunded", async () => {
+    const legacyPdas = getLegacyParticipantPdas(
+      bob.publicKey,
+      paymentId,
+      program.programId
+    );
+    const rent = await provider.connection.getBalance(legacyPdas.vault);
+    const balanceBefore = await provider.connection.getBalance(bob.publicKey);
+
+    // 9 SOL escrowed, only 8 SOL left to collect
+    await migrate(bob, paymentId);
+
+    const balanceAfter = await provider.connection.getBalance(bob.publicKey);
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    // Escrow and rent minus the transaction fee
+    expect(balanceAfter - balanceBefore).to.be.closeTo(
+      9 * anchor.web3.LAMPORTS_PER_SOL + rent,
+      10000
+    );
+    expect(vaultData.collectedAmount.toNumber()).equal(
+      2 * anchor.web3.LAMPORTS_PER_SOL
+    );
+    expect(
+      await provider.connection.getAccountInfo(
+        participantVaultOf(bob, paymentId)
+      )
+    ).to.be.null;
+    expect(await provider.connection.getAccountInfo(legacyPdas.vault)).to.be
+      .null;
+  });
+
+  it("Escrow of a legacy vault is refunded", async () => {
+    const { vault: legacyVault } = getVaultPdas(
+      issuer.publicKey,
+      legacyPaymentId,
+      program.programId
+    );
+    const legacyPdas = getLegacyParticipantPdas(
+      alice.publicKey,
+      legacyPaymentId,
+      program.programId
+    );
+    const rent = await provider.connection.getBalance(legacyPdas.vault);
+    const balanceBefore = await provider.connection.getBalance(alice.publicKey);
+
+    await migrate(alice, legacyPaymentId);
+
+    const balanceAfter = await provider.connection.getBalance(alice.publicKey);
+
+    expect(balanceAfter - balanceBefore).to.be.closeTo(
+      1.5 * anchor.web3.LAMPORTS_PER_SOL + rent,
+      10000
+    );
+    expect(
+      (await provider.connection.getAccountInfo(legacyVault)).data.length
+    ).equal(87);
+    expect(
+      await provider.connection.getAccountInfo(
+        participantVaultOf(alice, legacyPaymentId)
+      )
+    ).to.be.null;
+  });
+
+  function loadKeypair(name: string) {
+    return anchor.web3.Keypair.fromSecretKey(
+      Uint8Array.from(
+        JSON.parse(
+          fs.readFileSync(`tests/fixtures/${name}-keypair.json`, "utf8")
+        )
+      )
+    );
+  }
+
+  function participantVaultOf(
+    participant: anchor.web3.Keypair,
+    paymentId: number
+  ) {
+    return getParticipantPdas(
+      issuer.publicKey,
+      participant.publicKey,
+      paymentId,
+      program.programId
+    ).vault;
+  }
+
+  async function migrate(participant: anchor.web3.Keypair, paymentId: number) {
+    const legacyPdas = getLegacyParticipantPdas(
+      participant.publicKey,
+      paymentId,
+      program.programId
+    );
+    const pdas = getVaultPdas(issuer.publicKey, paymentId, program.programId);
+
+    await program.methods
+      .migrateParticipantVault(paymentId)
+      .accountsStrict({
+        participant: participant.publicKey,
+        issuer: issuer.publicKey,
+        vault: pdas.vault,
+        legacyParticipantVault: legacyPdas.vault,
+        legacyParticipantVaultAuthority: legacyPdas.vaultAuthority,
+        vaultAuthority: pdas.vaultAuthority,
+        participantVault: participantVaultOf(participant, paymentId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([participant])
+      .rpc();
+  }
+});
@@ -0,0 +1,97 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert, expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import { getIssuerProfilePda, getVaultPdas } from "./pdas";
+
+describe("Divi split modes", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const issuer = anchor.web3.Keypair.generate();
+  const debtors = [
+    anchor.web3.Keypair.generate().publicKey,
+    anchor.web3.Keypair.generate().publicKey,
+    anchor.web3.Keypair.generate().publicKey,
+  ];
+  let paymentId = Math.floor(Math.random() * 100000);
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+  });
+
+  it("Equal split of 100 lamports between 3 debtors", async () => {
+    const shares = await split(100, { equal: {} }, [0, 0, 0]);
+
+    expect(shares).to.deep.equal([34, 33, 33]);
+  });
+
+  it("Basis points split of 100 lamports", async () => {
+    const shares = await split(100, { basisPoints: {} }, [3333, 3333, 3334]);
+
+    expect(shares).to.deep.equal([34, 33, 33]);
+  });
+
+  it("Weighted split of 100 lamports", async () => {
+    const shares = await split(100, { weights: {} }, [1, 2, 4]);
+
+    expect(shares).to.deep.equal([15, 28, 57]);
+  });
+
+  it("Basis points must add up to 100%", async () => {
+    try {
+      await split(100, { basisPoints: {} }, [5000, 4000, 1000 - 1]);
+
+      assert.fail("Basis points not adding up to 10 000 should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "InvalidInvoiceShares"
+      );
+    }
+  });
+
+  async function split(
+    totalAmount: number,
+    splitMode: Parameters<typeof program.methods.initializeVault>[3],
+    values: number[]
+  ) {
+    paymentId += 1;
+
+    const { vault, vaultAuthority } = getVaultPdas(
+      issuer.publicKey,
+      paymentId,
+      program.programId
+    );
+
+    await program.methods
+      .initializeVault(
+        paymentId,
+        new anchor.BN(totalAmount),
+        null,
+        null,
+        splitMode,
+        debtors.map((participant, index) => ({
+          participant,
+          value: new anchor.BN(values[index]),
+        }))
+      )
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+        vault,
+        vaultAuthority,
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    return vaultData.shares.map((share) => share.amount.toNumber());
+  }
+});
@@ -0,0 +1,291 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  approveRelease,
+  cancelPayment,
+  chainTime,
+  claimRefund,
+  closeVault,
+  expectError,
+  initializeVault,
+  openDispute,
+  participate,
+  sol,
+} from "./helpers";
+import { getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi vault status transitions", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const issuer = anchor.web3.Keypair.generate();
+  const arbiter = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+  });
+
+  describe("Open vault", () => {
+    const paymentId = Math.floor(Math.random() * 100000);
+
+    before(async () => {
+      await createVault(paymentId, null);
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(4)
+      );
+    });
+
+    it("Open vault can't be released", async () => {
+      await expectStatus(paymentId, { open: {} });
+      await expectError(
+        closeVault(program, issuer, paymentId),
+        "VaultIsNotFinalized"
+      );
+      await expectError(
+        approveRelease(program, issuer.publicKey, paymentId, customerA),
+        "VaultIsNotFinalized"
+      );
+    });
+  });
+
+  describe("Funded vault", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 100000;
+
+    before(async () => {
+      await createVault(paymentId, null);
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(6)
+      );
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerB,
+        sol(4)
+      );
+    });
+
+    it("Funded vault can't be cancelled, paid or withdrawn", async () => {
+      await expectStatus(paymentId, { funded: {} });
+      await expectError(
+        cancelPayment(program, issuer, paymentId),
+        "VaultIsAlreadyFinalized"
+      );
+      await expectError(
+        participate(program, issuer.publicKey, paymentId, customerA, sol(1)),
+        "VaultIsAlreadyFinalized"
+      );
+      await expectError(
+        withdrawParticipation(paymentId, customerA, 6),
+        "VaultIsAlreadyFinalized"
+      );
+      await expectError(
+        claimRefund(program, issuer.publicKey, paymentId, customerA),
+        "RefundNotClaimable"
+      );
+    });
+  });
+
+  describe("Cancelled vault", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 200000;
+
+    before(async () => {
+      await createVault(paymentId, null);
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(4)
+      );
+      await cancelPayment(program, issuer, paymentId);
+    });
+
+    it("Cancelled vault can't leave the cancelled status", async () => {
+      await expectStatus(paymentId, { cancelled: {} });
+      await expectError(
+        cancelPayment(program, issuer, paymentId),
+        "VaultIsCancelled"
+      );
+      await expectError(
+        participate(program, issuer.publicKey, paymentId, customerB, sol(6)),
+        "VaultIsCancelled"
+      );
+      await expectError(
+        openDispute(program, issuer.publicKey, paymentId, customerA),
+        "VaultIsCancelled"
+      );
+      await expectError(
+        closeVault(program, issuer, paymentId),
+        "VaultIsCancelled"
+      );
+    });
+  });
+
+  describe("Expired vault", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 300000;
+    let expiresAt: number;
+
+    before(async () => {
+      expiresAt = (await chainTime(provider)) + 5;
+
+      await createVault(paymentId, new anchor.BN(expiresAt));
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(4)
+      );
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerB,
+        sol(2)
+      );
+
+      while ((await chainTime(provider)) <= expiresAt) {
+        await new Promise((resolve) => setTimeout(resolve, 1000));
+      }
+
+      await claimRefund(program, issuer.publicKey, paymentId, customerA);
+    });
+
+    it("First refund claimed after the deadline expires the vault", async () => {
+      await expectStatus(paymentId, { expired: {} });
+    });
+
+    it("Expired vault can't leave the expired status", async () => {
+      await expectError(
+        cancelPayment(program, issuer, paymentId),
+        "VaultIsExpired"
+      );
+      await expectError(
+        participate(program, issuer.publicKey, paymentId, customerA, sol(1)),
+        "VaultIsExpired"
+      );
+      await expectError(
+        openDispute(program, issuer.publicKey, paymentId, customerB),
+        "VaultIsExpired"
+      );
+      await expectError(
+        closeVault(program, issuer, paymentId),
+        "VaultIsExpired"
+      );
+    });
+  });
+
+  describe("Disputed vault", () => {
+    const paymentId = Math.floor(Math.random() * 100000) + 400000;
+
+    before(async () => {
+      await createVault(paymentId, null);
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerA,
+        sol(6)
+      );
+      await participate(
+        program,
+        issuer.publicKey,
+        paymentId,
+        customerB,
+        sol(4)
+      );
+      await openDispute(program, issuer.publicKey, paymentId, customerA);
+    });
+
+    it("Disputed vault waits for the arbiter", async () => {
+      await expectStatus(paymentId, { disputed: {} });
+      await expectError(
+        openDispute(program, issuer.publicKey, paymentId, customerB),
+        "VaultIsDisputed"
+      );
+      await expectError(
+        closeVault(program, issuer, paymentId),
+        "VaultIsDisputed"
+      );
+      await expectError(
+        claimRefund(program, issuer.publicKey, paymentId, customerA),
+        "VaultIsDisputed"
+      );
+      await expectError(
+        approveRelease(program, issuer.publicKey, paymentId, customerA),
+        "VaultIsDisputed"
+      );
+    });
+  });
+
+  async function expectStatus(paymentId: number, status: object) {
+    const vaultData = await program.account.paymentVault.fetch(
+      getVaultPdas(issuer.publicKey, paymentId, program.programId).vault
+    );
+
+    expect(vaultData.status).to.deep.equal(status);
+  }
+
+  async function createVault(paymentId: number, expiresAt: anchor.BN | null) {
+    await initializeVault(program, issuer, paymentId, { expiresAt });
+
+    await program.methods
+      .setArbiter(paymentId, arbiter.publicKey, null)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        vault: getVaultPdas(issuer.publicKey, paymentId, program.programId)
+          .vault,
+      })
+      .signers([issuer])
+      .rpc();
+  }
+
+  async function withdrawParticipation(
+    paymentId: number,
+    customer: anchor.web3.Keypair,
+    sol: number
+  ) {
+    const { vault, vaultAuthority } = getVaultPdas(
+      issuer.publicKey,
+      paymentId,
+      program.programId
+    );
+
+    await program.methods
+      .withdrawParticipation(
+        paymentId,
+        new anchor.BN(sol * anchor.web3.LAMPORTS_PER_SOL)
+      )
+      .accountsStrict({
+        participant: customer.publicKey,
+        vault,
+        vaultAuthority,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customer.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+  }
+});
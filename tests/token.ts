@@ -0,0 +1,642 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import {
+  ASSOCIATED_TOKEN_PROGRAM_ID,
+  ExtensionType,
+  TOKEN_2022_PROGRAM_ID,
+  TOKEN_PROGRAM_ID,
+  createAssociatedTokenAccount,
+  createInitializeMintInstruction,
+  createInitializeTransferFeeConfigInstruction,
+  createMint,
+  getAccount,
+  getAssociatedTokenAddressSync,
+  getMint,
+  getMintLen,
+  getTransferFeeConfig,
+  mintTo,
+  transferChecked,
+} from "@solana/spl-token";
+import { assert, expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import {
+  getIssuerProfilePda,
+  getMetadataPda,
+  getParticipantPdas,
+  getVaultPdas,
+} from "./pdas";
+
+const DECIMALS = 6;
+const ONE_TOKEN = 10 ** DECIMALS;
+
+describe("Divi token vaults", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+  });
+
+  describe("SPL Token (USDC like) vault", () => {
+    const paymentId = Math.floor(Math.random() * 100000);
+    const amount = new anchor.BN(10 * ONE_TOKEN);
+    const { vault, vaultAuthority } = getVaultPdas(
+      issuer.publicKey,
+      paymentId,
+      program.programId
+    );
+
+    let mint: anchor.web3.PublicKey;
+    let vaultTokenAccount: anchor.web3.PublicKey;
+
+    before(async () => {
+      mint = await createMint(
+        provider.connection,
+        issuer,
+        issuer.publicKey,
+        null,
+        DECIMALS
+      );
+
+      vaultTokenAccount = getAssociatedTokenAddressSync(
+        mint,
+        vaultAuthority,
+        true
+      );
+
+      for (const customer of [customerA, customerB]) {
+        const tokenAccount = await createAssociatedTokenAccount(
+          provider.connection,
+          customer,
+          mint,
+          customer.publicKey
+        );
+
+        await mintTo(
+          provider.connection,
+          issuer,
+          mint,
+          tokenAccount,
+          issuer,
+          100 * ONE_TOKEN
+        );
+      }
+    });
+
+    it("Create a token payment with 10 tokens", async () => {
+      await program.methods
+        .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          issuerProfile: getIssuerProfilePda(
+            issuer.publicKey,
+            program.programId
+          ),
+          vault,
+          vaultAuthority,
+          mint,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+
+      const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+      expect(vaultAccount.mint.equals(mint)).to.be.true;
+      expect(vaultAccount.decimals).equal(DECIMALS);
+      expect(vaultAccount.totalAmount.eq(amount)).to.be.true;
+    });
+
+    it("Customer A can't pay a token vault with SOL", async () => {
+      try {
+        await program.methods
+          .pay(paymentId, new anchor.BN(4))
+          .accountsStrict({
+            payer: customerA.publicKey,
+            issuer: issuer.publicKey,
+            vault,
+            vaultAuthority,
+            participantVault: getParticipantPdas(
+              issuer.publicKey,
+              customerA.publicKey,
+              paymentId,
+              program.programId
+            ).vault,
+            systemProgram: anchor.web3.SystemProgram.programId,
+          })
+          .signers([customerA])
+          .rpc();
+
+        assert.fail("SOL payment on a token vault should fail");
+      } catch (err) {
+        expect(err).to.be.instanceOf(anchor.AnchorError);
+        assert.strictEqual(
+          (err as anchor.AnchorError).error.errorCode.code,
+          "MintMismatch"
+        );
+      }
+    });
+
+    it("Customer A pay 6 tokens", async () => {
+      await payToken(customerA, mint, paymentId, 6 * ONE_TOKEN);
+
+      const vaultToken = await getAccount(
+        provider.connection,
+        vaultTokenAccount
+      );
+
+      expect(Number(vaultToken.amount)).equal(6 * ONE_TOKEN);
+    });
+
+    it("Customer B can't pay more than the remaining amount", async () => {
+      try {
+        await payToken(customerB, mint, paymentId, 5 * ONE_TOKEN);
+
+        assert.fail("Overpayment should fail");
+      } catch (err) {
+        expect(err).to.be.instanceOf(anchor.AnchorError);
+        assert.strictEqual(
+          (err as anchor.AnchorError).error.errorCode.code,
+          "AmountIsGreaterThanRemainingVaultAmount"
+        );
+      }
+    });
+
+    it("Customer B pay the last 4 tokens and finalize the vault", async () => {
+      await payToken(customerB, mint, paymentId, 4 * ONE_TOKEN);
+
+      const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+      expect(vaultAccount.status).to.deep.equal({ funded: {} });
+    });
+
+    it("Issuer close the token vault", async () => {
+      const issuerTokenAccount = getAssociatedTokenAddressSync(
+        mint,
+        issuer.publicKey
+      );
+
+      await program.methods
+        .closeVaultToken(paymentId)
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          recipient: issuer.publicKey,
+          vault,
+          vaultAuthority,
+          metadata: getMetadataPda(vault, program.programId),
+          mint,
+          vaultTokenAccount,
+          recipientTokenAccount: issuerTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+
+      const issuerToken = await getAccount(
+        provider.connection,
+        issuerTokenAccount
+      );
+
+      expect(Number(issuerToken.amount)).equal(10 * ONE_TOKEN);
+      expect(await provider.connection.getAccountInfo(vaultTokenAccount)).to.be
+        .null;
+      expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+    });
+  });
+
+  describe("SPL Token participation and refund", () => {
+    const paymentId = Math.floor(Math.random() * 100000);
+    const { vault, vaultAuthority } = getVaultPdas(
+      issuer.publicKey,
+      paymentId,
+      program.programId
+    );
+    const pdas = getParticipantPdas(
+      issuer.publicKey,
+      customerA.publicKey,
+      paymentId,
+      program.programId
+    );
+
+    let mint: anchor.web3.PublicKey;
+    let participantTokenAccount: anchor.web3.PublicKey;
+    let vaultTokenAccount: anchor.web3.PublicKey;
+
+    before(async () => {
+      mint = await createMint(
+        provider.connection,
+        issuer,
+        issuer.publicKey,
+        null,
+        DECIMALS
+      );
+
+      participantTokenAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        customerA,
+        mint,
+        customerA.publicKey
+      );
+
+      vaultTokenAccount = getAssociatedTokenAddressSync(
+        mint,
+        vaultAuthority,
+        true
+      );
+
+      await mintTo(
+        provider.connection,
+        issuer,
+        mint,
+        participantTokenAccount,
+        issuer,
+        100 * ONE_TOKEN
+      );
+
+      await program.methods
+        .initializeVault(
+          paymentId,
+          new anchor.BN(10 * ONE_TOKEN),
+          null,
+          null,
+          { exact: {} },
+          []
+        )
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          issuerProfile: getIssuerProfilePda(
+            issuer.publicKey,
+            program.programId
+          ),
+          vault,
+          vaultAuthority,
+          mint,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+    });
+
+    it("Customer A participate with 4 tokens", async () => {
+      await program.methods
+        .participateToken(paymentId, new anchor.BN(4 * ONE_TOKEN))
+        .accountsStrict({
+          participant: customerA.publicKey,
+          vault,
+          participantVault: pdas.vault,
+          vaultAuthority,
+          mint,
+          participantTokenAccount,
+          vaultTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([customerA])
+        .rpc();
+
+      const participant = await program.account.participantVault.fetch(
+        pdas.vault
+      );
+      const vaultToken = await getAccount(
+        provider.connection,
+        vaultTokenAccount
+      );
+      const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+      expect(participant.amount.toNumber()).equal(4 * ONE_TOKEN);
+      expect(Number(vaultToken.amount)).equal(4 * ONE_TOKEN);
+      expect(vaultAccount.status).to.deep.equal({ open: {} });
+    });
+
+    it("Issuer refund customer A tokens", async () => {
+      await program.methods
+        .refundParticipantToken(paymentId)
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          participant: customerA.publicKey,
+          vault,
+          vaultAuthority,
+          participantVault: pdas.vault,
+          mint,
+          vaultTokenAccount,
+          participantTokenAccount,
+          tokenProgram: TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+
+      const participantToken = await getAccount(
+        provider.connection,
+        participantTokenAccount
+      );
+
+      const vaultToken = await getAccount(
+        provider.connection,
+        vaultTokenAccount
+      );
+
+      expect(Number(participantToken.amount)).equal(100 * ONE_TOKEN);
+      expect(Number(vaultToken.amount)).equal(0);
+      expect(await provider.connection.getAccountInfo(pdas.vault)).to.be.null;
+    });
+  });
+
+  describe("Token-2022 vault with transfer fee", () => {
+    const paymentId = Math.floor(Math.random() * 100000);
+    const feeBasisPoints = 100; // 1%
+    const { vault, vaultAuthority } = getVaultPdas(
+      issuer.publicKey,
+      paymentId,
+      program.programId
+    );
+    const mintKeypair = anchor.web3.Keypair.generate();
+    const mint = mintKeypair.publicKey;
+    const vaultTokenAccount = getAssociatedTokenAddressSync(
+      mint,
+      vaultAuthority,
+      true,
+      TOKEN_2022_PROGRAM_ID
+    );
+    const issuerTokenAccount = getAssociatedTokenAddressSync(
+      mint,
+      issuer.publicKey,
+      false,
+      TOKEN_2022_PROGRAM_ID
+    );
+
+    let payerTokenAccount: anchor.web3.PublicKey;
+
+    before(async () => {
+      const mintLen = getMintLen([ExtensionType.TransferFeeConfig]);
+      const lamports =
+        await provider.connection.getMinimumBalanceForRentExemption(mintLen);
+
+      await anchor.web3.sendAndConfirmTransaction(
+        provider.connection,
+        new anchor.web3.Transaction().add(
+          anchor.web3.SystemProgram.createAccount({
+            fromPubkey: issuer.publicKey,
+            newAccountPubkey: mint,
+            space: mintLen,
+            lamports,
+            programId: TOKEN_2022_PROGRAM_ID,
+          }),
+          createInitializeTransferFeeConfigInstruction(
+            mint,
+            issuer.publicKey,
+            issuer.publicKey,
+            feeBasisPoints,
+            BigInt(ONE_TOKEN),
+            TOKEN_2022_PROGRAM_ID
+          ),
+          createInitializeMintInstruction(
+            mint,
+            DECIMALS,
+            issuer.publicKey,
+            null,
+            TOKEN_2022_PROGRAM_ID
+          )
+        ),
+        [issuer, mintKeypair]
+      );
+
+      payerTokenAccount = await createAssociatedTokenAccount(
+        provider.connection,
+        customerA,
+        mint,
+        customerA.publicKey,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await mintTo(
+        provider.connection,
+        issuer,
+        mint,
+        payerTokenAccount,
+        issuer,
+        100 * ONE_TOKEN,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      await program.methods
+        .initializeVault(
+          paymentId,
+          new anchor.BN(10 * ONE_TOKEN),
+          null,
+          null,
+          { exact: {} },
+          []
+        )
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          issuerProfile: getIssuerProfilePda(
+            issuer.publicKey,
+            program.programId
+          ),
+          vault,
+          vaultAuthority,
+          mint,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+    });
+
+    it("Only the amount net of transfer fee counts toward the vault", async () => {
+      await payFeeToken(10 * ONE_TOKEN);
+
+      const vaultToken = await getAccount(
+        provider.connection,
+        vaultTokenAccount,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+      const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+      const ledger = await program.account.participantVault.fetch(
+        getParticipantPdas(
+          issuer.publicKey,
+          customerA.publicKey,
+          paymentId,
+          program.programId
+        ).vault
+      );
+      const netAmount =
+        10 * ONE_TOKEN - (10 * ONE_TOKEN * feeBasisPoints) / 10_000;
+
+      expect(Number(vaultToken.amount)).equal(netAmount);
+      expect(ledger.amount.toNumber()).equal(netAmount);
+      expect(vaultAccount.status).to.deep.equal({ open: {} });
+    });
+
+    it("Issuer close the funded vault, withheld fees go to the mint", async () => {
+      // 1% fee rounded up, 101011 pays exactly the 0.1 token left
+      await payFeeToken(101011);
+
+      await program.methods
+        .closeVaultToken(paymentId)
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          recipient: issuer.publicKey,
+          vault,
+          vaultAuthority,
+          metadata: getMetadataPda(vault, program.programId),
+          mint,
+          vaultTokenAccount,
+          recipientTokenAccount: issuerTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(vault)).to.be.null;
+      expect(await provider.connection.getAccountInfo(vaultTokenAccount)).to
+        .be.null;
+      expect(Number(await withheldByMint())).to.be.greaterThan(0);
+    });
+
+    it("Issuer recover tokens sent to the closed fee vault", async () => {
+      await createAssociatedTokenAccount(
+        provider.connection,
+        customerA,
+        mint,
+        vaultAuthority,
+        undefined,
+        TOKEN_2022_PROGRAM_ID,
+        ASSOCIATED_TOKEN_PROGRAM_ID,
+        true
+      );
+      await transferChecked(
+        provider.connection,
+        customerA,
+        payerTokenAccount,
+        mint,
+        vaultTokenAccount,
+        customerA,
+        5 * ONE_TOKEN,
+        DECIMALS,
+        [],
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      const withheldBefore = await withheldByMint();
+
+      await program.methods
+        .recoverVaultAuthorityToken(paymentId)
+        .accountsStrict({
+          issuer: issuer.publicKey,
+          vault,
+          vaultAuthority,
+          mint,
+          vaultTokenAccount,
+          issuerTokenAccount,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+
+      expect(await provider.connection.getAccountInfo(vaultTokenAccount)).to
+        .be.null;
+      // The fee withheld on the donation is harvested as well
+      expect(Number(await withheldByMint())).to.be.greaterThan(
+        Number(withheldBefore)
+      );
+    });
+
+    async function payFeeToken(amount: number) {
+      await program.methods
+        .payToken(paymentId, new anchor.BN(amount))
+        .accountsStrict({
+          payer: customerA.publicKey,
+          issuer: issuer.publicKey,
+          vault,
+          vaultAuthority,
+          mint,
+          payerTokenAccount,
+          vaultTokenAccount,
+          participantVault: getParticipantPdas(
+            issuer.publicKey,
+            customerA.publicKey,
+            paymentId,
+            program.programId
+          ).vault,
+          tokenProgram: TOKEN_2022_PROGRAM_ID,
+          associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([customerA])
+        .rpc();
+    }
+
+    async function withheldByMint() {
+      const mintInfo = await getMint(
+        provider.connection,
+        mint,
+        undefined,
+        TOKEN_2022_PROGRAM_ID
+      );
+
+      return getTransferFeeConfig(mintInfo).withheldAmount;
+    }
+  });
+
+  async function payToken(
+    payer: anchor.web3.Keypair,
+    mint: anchor.web3.PublicKey,
+    paymentId: number,
+    amount: number
+  ) {
+    const { vault, vaultAuthority } = getVaultPdas(
+      issuer.publicKey,
+      paymentId,
+      program.programId
+    );
+
+    return program.methods
+      .payToken(paymentId, new anchor.BN(amount))
+      .accountsStrict({
+        payer: payer.publicKey,
+        issuer: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        mint,
+        payerTokenAccount: getAssociatedTokenAddressSync(mint, payer.publicKey),
+        vaultTokenAccount: getAssociatedTokenAddressSync(
+          mint,
+          vaultAuthority,
+          true
+        ),
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          payer.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        tokenProgram: TOKEN_PROGRAM_ID,
+        associatedTokenProgram: ASSOCIATED_TOKEN_PROGRAM_ID,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([payer])
+      .rpc();
+  }
+});
@@ -0,0 +1,161 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import { authorityRent } from "./helpers";
+import { getIssuerProfilePda, getParticipantPdas, getVaultPdas } from "./pdas";
+
+/**
+ * Convert a display amount (e.g. 0.35 SOL) to base units with the vault decimals
+ */
+function toBaseUnits(amount: string, decimals: number) {
+  const [whole, fraction = ""] = amount.split(".");
+
+  return new anchor.BN(whole + fraction.padEnd(decimals, "0"));
+}
+
+describe("Divi amount units", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const customerC = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+
+  let decimals: number;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+    await airdrop(provider, customerC.publicKey, 100);
+
+    await program.methods
+      .initializeVault(
+        paymentId,
+        toBaseUnits("1", 9),
+        null,
+        null,
+        { exact: {} },
+        []
+      )
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+        vault,
+        vaultAuthority,
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    decimals = (await program.account.paymentVault.fetch(vault)).decimals;
+  });
+
+  it("Native vaults store the SOL decimals", async () => {
+    expect(decimals).equal(9);
+  });
+
+  it("The issuer funds the vault authority rent", async () => {
+    // Contributions below the rent exempt minimum can be escrowed
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      await authorityRent(provider)
+    );
+  });
+
+  it("Customer A pay 0.35 SOL with pay", async () => {
+    await pay(customerA, toBaseUnits("0.35", decimals));
+
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      0.35 * anchor.web3.LAMPORTS_PER_SOL + (await authorityRent(provider))
+    );
+  });
+
+  it("Customer B participate with 0.4 SOL, in the same units as pay", async () => {
+    await program.methods
+      .participate(paymentId, toBaseUnits("0.4", decimals))
+      .accountsStrict({
+        participant: customerB.publicKey,
+        vault,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customerB.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customerB])
+      .rpc();
+
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      0.75 * anchor.web3.LAMPORTS_PER_SOL + (await authorityRent(provider))
+    );
+  });
+
+  describe("Migration from whole SOL pay amounts", () => {
+    it("A legacy whole SOL amount is now read as lamports", async () => {
+      // Before, `pay(1)` meant 1 SOL and was multiplied by LAMPORTS_PER_SOL
+      const balanceBefore = await provider.connection.getBalance(
+        vaultAuthority
+      );
+
+      await pay(customerC, new anchor.BN(1));
+
+      const ledger = await program.account.participantVault.fetch(
+        getParticipantPdas(
+          issuer.publicKey,
+          customerC.publicKey,
+          paymentId,
+          program.programId
+        ).vault
+      );
+
+      expect(ledger.amount.toNumber()).equal(1);
+      expect(
+        (await provider.connection.getBalance(vaultAuthority)) - balanceBefore
+      ).equal(1);
+    });
+
+    it("Paying the exact remaining base units finalizes the vault", async () => {
+      const remaining = toBaseUnits("0.25", decimals).subn(1);
+
+      await pay(customerC, remaining);
+
+      const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+      expect(vaultAccount.status).to.deep.equal({ funded: {} });
+    });
+  });
+
+  async function pay(payer: anchor.web3.Keypair, amount: anchor.BN) {
+    return program.methods
+      .pay(paymentId, amount)
+      .accountsStrict({
+        payer: payer.publicKey,
+        issuer: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          payer.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([payer])
+      .rpc();
+  }
+});
@@ -3,12 +3,14 @@ import { Program } from "@coral-xyz/anchor";
 import { assert, expect } from "chai";
 import { Divi } from "../target/types/divi";
 import { airdrop } from "./airdrop";
+import { authorityRent, expectError, participate, sol } from "./helpers";
+import { VAULT, VAULT_AUTHORITY } from "./constants";
 import {
-  PARTICIPANT_VAULT,
-  PARTICIPANT_VAULT_AUTHORITY,
-  VAULT,
-  VAULT_AUTHORITY,
-} from "./constants";
+  getIssuerProfilePda,
+  getMetadataPda,
+  getParticipantPdas,
+  getVaultPdas,
+} from "./pdas";
 
 describe("Divi v2", () => {
   const provider = anchor.AnchorProvider.env();
@@ -50,11 +52,13 @@ describe("Divi v2", () => {
 
   it("Create payment with 10 SOL", async () => {
     const tx = await program.methods
-      .initializeVault(paymentId, amount)
+      .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
       .accountsStrict({
         issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
         vault,
         vaultAuthority,
+        mint: null,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([issuer])
@@ -66,12 +70,13 @@ describe("Divi v2", () => {
 
     expect(vaultAccount.issuer.equals(issuer.publicKey)).to.be.true;
     expect(vaultAccount.totalAmount.eq(amount)).to.be.true;
-    expect(vaultAccount.isFinalized).to.be.false;
+    expect(vaultAccount.status).to.deep.equal({ open: {} });
     expect(vaultAccount.paymentId).equal(paymentId);
   });
 
   it("Customer A participate with 4 SOL", async () => {
     const pdas = getParticipantPdas(
+      issuer.publicKey,
       customerA.publicKey,
       paymentId,
       program.programId
@@ -85,7 +90,7 @@ describe("Divi v2", () => {
         participant: customerA.publicKey,
         vault,
         participantVault: pdas.vault,
-        participantVaultAuthority: pdas.vaultAuthority,
+        vaultAuthority,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([customerA])
@@ -105,6 +110,7 @@ describe("Divi v2", () => {
 
   it("Customer B participate with 4 SOL", async () => {
     const pdas = getParticipantPdas(
+      issuer.publicKey,
       customerB.publicKey,
       paymentId,
       program.programId
@@ -118,7 +124,7 @@ describe("Divi v2", () => {
         participant: customerB.publicKey,
         vault,
         participantVault: pdas.vault,
-        participantVaultAuthority: pdas.vaultAuthority,
+        vaultAuthority,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([customerB])
@@ -136,14 +142,15 @@ describe("Divi v2", () => {
     expect(participant.issuer.equals(issuer.publicKey)).to.be.true;
   });
 
-  it("Customer C participate with 2 SOL", async () => {
+  it("Customer C participate with 1 SOL", async () => {
     const pdas = getParticipantPdas(
+      issuer.publicKey,
       customerC.publicKey,
       paymentId,
       program.programId
     );
 
-    const participationAmount = new anchor.BN(2 * anchor.web3.LAMPORTS_PER_SOL);
+    const participationAmount = new anchor.BN(1 * anchor.web3.LAMPORTS_PER_SOL);
 
     const tx = await program.methods
       .participate(paymentId, participationAmount)
@@ -151,7 +158,7 @@ describe("Divi v2", () => {
         participant: customerC.publicKey,
         vault,
         participantVault: pdas.vault,
-        participantVaultAuthority: pdas.vaultAuthority,
+        vaultAuthority,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
       .signers([customerC])
@@ -169,38 +176,37 @@ describe("Divi v2", () => {
     expect(participant.issuer.equals(issuer.publicKey)).to.be.true;
   });
 
-  it("Issuer cancel the payment, but failed because participant are not refunded", async () => {
+  it("Participations are held by the vault authority and count toward the vault", async () => {
+    const balance = await provider.connection.getBalance(vaultAuthority);
+    const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+    expect(balance).equal(
+      9 * anchor.web3.LAMPORTS_PER_SOL + (await authorityRent(provider))
+    );
+    expect(vaultAccount.status).to.deep.equal({ open: {} });
+  });
+
+  it("Issuer close the payment, but failed because participant are not refunded", async () => {
     try {
-      const tx = await program.methods
-        .cancelPayment(paymentId)
+      await program.methods
+        .closePaymentVault(paymentId)
         .accountsStrict({
           vault,
           vaultAuthority,
+          metadata: getMetadataPda(vault, program.programId),
           issuer: issuer.publicKey,
           systemProgram: anchor.web3.SystemProgram.programId,
         })
-        .remainingAccounts(
-          (
-            await program.account.participantVault.all()
-          ).map((p) => ({
-            pubkey: p.publicKey,
-            isWritable: true,
-            isSigner: false,
-          }))
-        )
         .signers([issuer])
         .rpc();
 
-      console.log(tx);
+      assert.fail("Close with participants left should fail");
     } catch (err) {
-      if (err instanceof anchor.AnchorError) {
-        assert.strictEqual(
-          err.error.errorCode.code,
-          "NotAllParticipantsRefunded"
-        );
-      } else {
-        console.error(err);
-      }
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "NotAllParticipantsRefunded"
+      );
     }
   });
 
@@ -218,6 +224,7 @@ describe("Divi v2", () => {
 
     for await (const participant of participants) {
       const pdas = getParticipantPdas(
+        issuer.publicKey,
         participant.account.participant,
         paymentId,
         program.programId
@@ -227,7 +234,7 @@ describe("Divi v2", () => {
         .refundParticipant(paymentId)
         .accountsStrict({
           vault,
-          participantVaultAuthority: pdas.vaultAuthority,
+          vaultAuthority,
           participantVault: pdas.vault,
           issuer: issuer.publicKey,
           participant: participant.account.participant,
@@ -239,9 +246,19 @@ describe("Divi v2", () => {
       console.log(tx);
     }
 
-    const pts = await program.account.participantVault.all();
+    const pts = await program.account.participantVault.all([
+      {
+        memcmp: {
+          offset: 8,
+          bytes: issuer.publicKey.toBase58(),
+        },
+      },
+    ]);
 
     expect(pts.length).eq(0);
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      await authorityRent(provider)
+    );
   });
 
   it("Issuer close the payment", async () => {
@@ -249,6 +266,8 @@ describe("Divi v2", () => {
       .closePaymentVault(paymentId)
       .accountsStrict({
         vault,
+        vaultAuthority,
+        metadata: getMetadataPda(vault, program.programId),
         issuer: issuer.publicKey,
         systemProgram: anchor.web3.SystemProgram.programId,
       })
@@ -268,33 +287,111 @@ describe("Divi v2", () => {
   });
 });
 
-function getParticipantPdas(
-  participant: anchor.web3.PublicKey,
-  paymentId: number,
-  programId: anchor.web3.PublicKey
-) {
-  const paymentIdBuffer = new anchor.BN(paymentId).toArrayLike(Buffer, "le", 4);
+describe("Divi v2 fully funded by participations", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
 
-  const [vault] = anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      anchor.utils.bytes.utf8.encode(PARTICIPANT_VAULT),
-      participant.toBuffer(),
-      paymentIdBuffer,
-    ],
-    programId
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const amount = new anchor.BN(6 * anchor.web3.LAMPORTS_PER_SOL);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
   );
 
-  const [vaultAuthority] = anchor.web3.PublicKey.findProgramAddressSync(
-    [
-      anchor.utils.bytes.utf8.encode(PARTICIPANT_VAULT_AUTHORITY),
-      participant.toBuffer(),
-      paymentIdBuffer,
-    ],
-    programId
-  );
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
 
-  return {
-    vault,
-    vaultAuthority,
-  };
-}
+    await program.methods
+      .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+        vault,
+        vaultAuthority,
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+  });
+
+  it("Customer can't participate with a zero amount", async () => {
+    await expectError(
+      participate(program, issuer.publicKey, paymentId, customerA, sol(0)),
+      "InvalidAmount"
+    );
+  });
+
+  it("The last participation finalizes the vault", async () => {
+    await participate(program, issuer.publicKey, paymentId, customerA, sol(4));
+    await participate(program, issuer.publicKey, paymentId, customerB, sol(2));
+
+    const vaultAccount = await program.account.paymentVault.fetch(vault);
+
+    expect(vaultAccount.status).to.deep.equal({ funded: {} });
+  });
+
+  it("Participations can't be refunded once the vault is finalized", async () => {
+    try {
+      await program.methods
+        .refundParticipant(paymentId)
+        .accountsStrict({
+          vault,
+          vaultAuthority,
+          participantVault: getParticipantPdas(
+            issuer.publicKey,
+            customerA.publicKey,
+            paymentId,
+            program.programId
+          ).vault,
+          issuer: issuer.publicKey,
+          participant: customerA.publicKey,
+          systemProgram: anchor.web3.SystemProgram.programId,
+        })
+        .signers([issuer])
+        .rpc();
+
+      assert.fail("Refund of a finalized vault should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "VaultIsAlreadyFinalized"
+      );
+    }
+  });
+
+  it("Issuer close the vault and receive every participation", async () => {
+    const balanceBefore = await provider.connection.getBalance(
+      issuer.publicKey
+    );
+
+    await program.methods
+      .closeVault(paymentId)
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        recipient: issuer.publicKey,
+        vault,
+        vaultAuthority,
+        metadata: getMetadataPda(vault, program.programId),
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    const balanceAfter = await provider.connection.getBalance(
+      issuer.publicKey
+    );
+
+    expect(balanceAfter - balanceBefore).to.be.greaterThan(
+      5 * anchor.web3.LAMPORTS_PER_SOL
+    );
+  });
+});
@@ -0,0 +1,149 @@
+import * as anchor from "@coral-xyz/anchor";
+import { Program } from "@coral-xyz/anchor";
+import { assert, expect } from "chai";
+import { Divi } from "../target/types/divi";
+import { airdrop } from "./airdrop";
+import { authorityRent } from "./helpers";
+import { getIssuerProfilePda, getParticipantPdas, getVaultPdas } from "./pdas";
+
+describe("Divi participation withdrawal", () => {
+  const provider = anchor.AnchorProvider.env();
+  anchor.setProvider(provider);
+
+  const program = anchor.workspace.Divi as Program<Divi>;
+  const paymentId = Math.floor(Math.random() * 100000);
+  const amount = new anchor.BN(10 * anchor.web3.LAMPORTS_PER_SOL);
+  const issuer = anchor.web3.Keypair.generate();
+  const customerA = anchor.web3.Keypair.generate();
+  const customerB = anchor.web3.Keypair.generate();
+  const { vault, vaultAuthority } = getVaultPdas(
+    issuer.publicKey,
+    paymentId,
+    program.programId
+  );
+  const participantVault = getParticipantPdas(
+    issuer.publicKey,
+    customerA.publicKey,
+    paymentId,
+    program.programId
+  ).vault;
+
+  before(async () => {
+    await airdrop(provider, issuer.publicKey, 100);
+    await airdrop(provider, customerA.publicKey, 100);
+    await airdrop(provider, customerB.publicKey, 100);
+
+    await program.methods
+      .initializeVault(paymentId, amount, null, null, { exact: {} }, [])
+      .accountsStrict({
+        issuer: issuer.publicKey,
+        issuerProfile: getIssuerProfilePda(issuer.publicKey, program.programId),
+        vault,
+        vaultAuthority,
+        mint: null,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([issuer])
+      .rpc();
+
+    await program.methods
+      .participate(paymentId, new anchor.BN(5 * anchor.web3.LAMPORTS_PER_SOL))
+      .accountsStrict({
+        participant: customerA.publicKey,
+        vault,
+        participantVault,
+        vaultAuthority,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customerA])
+      .rpc();
+  });
+
+  it("Customer A withdraw 2 SOL of his participation", async () => {
+    const balanceBefore = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+
+    await withdraw(customerA, 2);
+
+    const balanceAfter = await provider.connection.getBalance(
+      customerA.publicKey
+    );
+    const ledger = await program.account.participantVault.fetch(
+      participantVault
+    );
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(balanceAfter - balanceBefore).to.be.closeTo(
+      2 * anchor.web3.LAMPORTS_PER_SOL,
+      10000
+    );
+    expect(ledger.amount.toNumber()).equal(3 * anchor.web3.LAMPORTS_PER_SOL);
+    expect(vaultData.collectedAmount.toNumber()).equal(
+      3 * anchor.web3.LAMPORTS_PER_SOL
+    );
+  });
+
+  it("Customer A can't withdraw more than his participation", async () => {
+    try {
+      await withdraw(customerA, 4);
+
+      assert.fail("Withdrawal above the participation should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "InvalidWithdrawalAmount"
+      );
+    }
+  });
+
+  it("Customer B can't withdraw from a participation he doesn't have", async () => {
+    try {
+      await withdraw(customerB, 1);
+
+      assert.fail("Withdrawal without participation should fail");
+    } catch (err) {
+      expect(err).to.be.instanceOf(anchor.AnchorError);
+      assert.strictEqual(
+        (err as anchor.AnchorError).error.errorCode.code,
+        "AccountNotInitialized"
+      );
+    }
+  });
+
+  it("Customer A withdraw the rest and close his participant vault", async () => {
+    await withdraw(customerA, 3);
+
+    const vaultData = await program.account.paymentVault.fetch(vault);
+
+    expect(await provider.connection.getAccountInfo(participantVault)).to.be
+      .null;
+    expect(await provider.connection.getBalance(vaultAuthority)).equal(
+      await authorityRent(provider)
+    );
+    expect(vaultData.collectedAmount.toNumber()).equal(0);
+  });
+
+  async function withdraw(customer: anchor.web3.Keypair, sol: number) {
+    await program.methods
+      .withdrawParticipation(
+        paymentId,
+        new anchor.BN(sol * anchor.web3.LAMPORTS_PER_SOL)
+      )
+      .accountsStrict({
+        participant: customer.publicKey,
+        vault,
+        vaultAuthority,
+        participantVault: getParticipantPdas(
+          issuer.publicKey,
+          customer.publicKey,
+          paymentId,
+          program.programId
+        ).vault,
+        systemProgram: anchor.web3.SystemProgram.programId,
+      })
+      .signers([customer])
+      .rpc();
+  }
+});